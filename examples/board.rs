@@ -0,0 +1,140 @@
+mod helpers;
+
+use bevy::prelude::*;
+use bevy_card3d_kit::board::{Board, BoardPart};
+use bevy_card3d_kit::prelude::{Card, Card3DPlugins, Moveable, SharkCamera};
+use bevy_card3d_kit::zone::browse::{CloseZoneBrowser, OpenZoneBrowser, ZoneBrowser};
+use bevy_card3d_kit::zone::deck_zone::{DeckCard, DrawCard};
+use bevy_card3d_kit::zone::desk_zone::DeskZone;
+use bevy_card3d_kit::zone::events::{CardOnZone, ZoneRejected};
+use bevy_card3d_kit::zone::send_card_to;
+use bevy_card3d_kit::zone::visibility::{LocalViewer, PlayerId};
+use bevy_inspector_egui::bevy_egui::EguiPlugin;
+use bevy_inspector_egui::quick::WorldInspectorPlugin;
+use helpers::*;
+
+// 开启热重载 cargo run --example board --features bevy/file_watcher
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, Card3DPlugins, SimplePlugin))
+        .add_plugins(EguiPlugin {
+            enable_multipass_for_primary_context: true,
+        })
+        .add_plugins(WorldInspectorPlugin::new())
+        .insert_resource(LocalViewer(Some(PlayerId(0))))
+        .add_systems(Startup, setup)
+        .add_systems(Update, (fill_deck, keyboard_system))
+        .add_observer(card_on_zone)
+        .add_observer(zone_rejected)
+        .run();
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    // 相机
+    commands.spawn((
+        SharkCamera,
+        Camera3d::default(),
+        Transform::from_xyz(0., 0., 25.).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    // 光源
+    commands.spawn((
+        PointLight {
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, 10.0),
+    ));
+
+    commands.spawn((
+        Text::new(
+            "Press `D` to draw a card\nPress `B` to browse the graveyard\nPress `V` to switch the viewer\nEdit assets/boards/duel.board.ron to change the board",
+        ),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+    ));
+
+    commands.spawn(Board(asset_server.load("boards/duel.board.ron")));
+}
+
+/// 牌堆生成后放入卡片
+fn fill_deck(mut commands: Commands, query: Query<(Entity, &BoardPart), Added<BoardPart>>) {
+    for (entity, part) in query.iter() {
+        if part.name != "deck" {
+            continue;
+        }
+        for index in 0..20 {
+            commands.spawn((
+                Card {
+                    origin: Transform::default(),
+                },
+                CardInfo {
+                    name: if index % 2 == 0 {
+                        "NAAI-A-001".to_string()
+                    } else {
+                        "S001-A-001".to_string()
+                    },
+                },
+                DeckCard {
+                    belongs_to_deck: Some(entity),
+                },
+            ));
+        }
+    }
+}
+
+fn keyboard_system(
+    input: Res<ButtonInput<KeyCode>>,
+    mut commands: Commands,
+    query: Query<(Entity, &BoardPart)>,
+    query_browser: Query<(), With<ZoneBrowser>>,
+    mut viewer: ResMut<LocalViewer>,
+) {
+    // 切换为旁观者 手牌只能看到背面
+    if input.just_pressed(KeyCode::KeyV) {
+        viewer.0 = match viewer.0 {
+            Some(_) => None,
+            None => Some(PlayerId(0)),
+        };
+    }
+    let find = |name: &str| {
+        query
+            .iter()
+            .find(|(_, part)| part.name == name)
+            .map(|(entity, _)| entity)
+    };
+    // 展开或收起墓地
+    if input.just_pressed(KeyCode::KeyB) {
+        if !query_browser.is_empty() {
+            commands.trigger(CloseZoneBrowser);
+        } else if let Some(zone) = find("graveyard") {
+            commands.trigger(OpenZoneBrowser { zone });
+        }
+    }
+    if !input.just_pressed(KeyCode::KeyD) {
+        return;
+    }
+    if let (Some(deck), Some(to)) = (find("deck"), find("hand")) {
+        commands.trigger(DrawCard { deck, to });
+    }
+}
+
+fn card_on_zone(
+    card_on_zone: Trigger<CardOnZone>,
+    mut commands: Commands,
+    query_desk: Query<(), With<DeskZone>>,
+) {
+    if query_desk.contains(card_on_zone.zone) {
+        commands.entity(card_on_zone.card).remove::<Moveable>();
+        send_card_to(&mut commands, card_on_zone.card, card_on_zone.zone);
+    }
+}
+
+// 场地满了 卡片回到原处后可以继续拖拽
+fn zone_rejected(zone_rejected: Trigger<ZoneRejected>, mut commands: Commands) {
+    commands.entity(zone_rejected.card).insert(Moveable);
+}
@@ -1,6 +1,7 @@
 mod helpers;
 
 use bevy::prelude::*;
+use bevy_card3d_kit::prelude::attach::AttachedTo;
 use bevy_card3d_kit::prelude::{Card, Card3DPlugins, HAND_CARD_LEVEL, Moveable, SharkCamera};
 use bevy_card3d_kit::zone::events::CardOnCard;
 use bevy_inspector_egui::bevy_egui::EguiPlugin;
@@ -56,7 +57,10 @@ fn setup(mut commands: Commands) {
     ));
 }
 
-fn card_on_zone(card_on_card: Trigger<CardOnCard>) {
+fn card_on_zone(card_on_card: Trigger<CardOnCard>, mut commands: Commands) {
     info!("{:?}", card_on_card.clone());
-    // do your own logic here
+    // 放上去的卡片附着在下面的卡片上 之后跟随它移动
+    commands
+        .entity(card_on_card.top_card)
+        .insert(AttachedTo(card_on_card.bottom_card));
 }
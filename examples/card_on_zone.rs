@@ -5,13 +5,15 @@ use bevy::DefaultPlugins;
 use bevy::app::{App, Startup};
 use bevy::color::palettes::css::{GREEN, RED};
 use bevy::prelude::*;
-use bevy_card3d_kit::highlight::Highlight;
 use bevy_card3d_kit::prelude::{
     Card, Card3DPlugins, CardLine, Dragged, HAND_CARD_LEVEL, HandCard, HandCardChanged, Moveable,
     SharkCamera,
 };
 use bevy_card3d_kit::tween::animation::card_set_on_zone_animation;
-use bevy_card3d_kit::zone::events::CardOnZone;
+use bevy_card3d_kit::zone::drop_filter::{DropFilter, bind_drop_filter};
+use bevy_card3d_kit::zone::events::{CardDropRejected, CardOnZone};
+use bevy_card3d_kit::zone::shape::ZoneShape;
+use bevy_card3d_kit::zone::zone_highlight::{ZoneHighlight, ZoneHighlightStyle};
 use bevy_card3d_kit::zone::{Zone, ZoneMaterialGetter, bind_zone_render};
 use bevy_inspector_egui::bevy_egui::EguiPlugin;
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
@@ -25,9 +27,11 @@ fn main() {
         .add_plugins(WorldInspectorPlugin::new())
         .add_systems(Startup, setup)
         .add_observer(card_on_zone)
+        .add_observer(card_drop_rejected)
         .init_resource::<CardLineEntity>()
         .add_plugins(|app: &mut App| {
             bind_zone_render::<ConditionZone>(app);
+            bind_drop_filter::<ConditionZone, CardInfo>(app);
         })
         .run();
 }
@@ -55,9 +59,9 @@ fn setup(mut commands: Commands) {
             center: Transform::from_xyz(-3.0, 0.0, 0.0),
         },
         ConditionZone::CanSet,
-        // FIXME: 层级问题。希望这个outline 在下方!
-        Highlight {
+        ZoneHighlight {
             color: Color::WHITE,
+            style: ZoneHighlightStyle::Border { width: 0.1 },
         },
     ));
     commands.spawn((
@@ -66,6 +70,7 @@ fn setup(mut commands: Commands) {
             center: Transform::from_xyz(3.0, 0.0, 0.0),
         },
         ConditionZone::NotCanSet,
+        ZoneShape::Circle,
     ));
 
     let card_list = [
@@ -155,6 +160,10 @@ fn card_on_zone(
     };
 }
 
+fn card_drop_rejected(rejected: Trigger<CardDropRejected>) {
+    info!("Rejected {:?}", rejected.event());
+}
+
 #[derive(Resource, Default)]
 pub struct CardLineEntity(Option<Entity>);
 
@@ -195,3 +204,9 @@ impl ZoneMaterialGetter for ConditionZone {
         }
     }
 }
+
+impl DropFilter<CardInfo> for ConditionZone {
+    fn accept(&self, _card: &CardInfo) -> bool {
+        matches!(self, ConditionZone::CanSet)
+    }
+}
@@ -0,0 +1,182 @@
+mod helpers;
+
+use bevy::color::palettes::css::DARK_SLATE_GRAY;
+use bevy::prelude::*;
+use bevy_card3d_kit::choice::{CardChoice, ChoiceMade};
+use bevy_card3d_kit::mulligan::{Mulligan, MulliganFinished, MulliganRule};
+use bevy_card3d_kit::prelude::{Card, Card3DPlugins, CardLine, HAND_CARD_LEVEL, SharkCamera};
+use bevy_card3d_kit::zone::deck_zone::{CardDrawn, DeckCard, DeckZone, DrawCard, ShuffleDeck};
+use bevy_card3d_kit::zone::{Zone, ZoneMaterialGetter, bind_zone_render, send_card_to};
+use bevy_inspector_egui::bevy_egui::EguiPlugin;
+use bevy_inspector_egui::quick::WorldInspectorPlugin;
+use helpers::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, Card3DPlugins, SimplePlugin))
+        .add_plugins(EguiPlugin {
+            enable_multipass_for_primary_context: true,
+        })
+        .add_plugins(WorldInspectorPlugin::new())
+        .add_systems(Startup, setup)
+        .add_systems(Update, keyboard_system)
+        .add_observer(card_drawn)
+        .add_observer(choice_made)
+        .add_observer(mulligan_finished)
+        .add_plugins(|app: &mut App| {
+            bind_zone_render::<DeckInfo>(app);
+        })
+        .run();
+}
+
+#[derive(Resource, Clone, Copy)]
+struct Entities {
+    deck: Entity,
+    card_line: Entity,
+}
+
+fn setup(mut commands: Commands) {
+    // 相机
+    commands.spawn((
+        SharkCamera,
+        Camera3d::default(),
+        Transform::from_xyz(0., 0., 25.).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    // 光源
+    commands.spawn((
+        PointLight {
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, 10.0),
+    ));
+
+    commands.spawn((
+        Text::new("Press `D` to draw a card\nPress `S` to shuffle the deck\nPress `C` to pick one of the top 3 cards\nPress `M` or `L` to mulligan the hand (free or London)"),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+    ));
+
+    let deck = commands
+        .spawn((
+            Zone {
+                center: Transform::from_xyz(12., 0., 0.0),
+                size: Vec2::new(3.0, 4.0),
+            },
+            DeckZone::default(),
+            DeckInfo,
+        ))
+        .id();
+    let card_line = commands
+        .spawn(CardLine {
+            transform: Transform::from_xyz(0.0, -6.7, HAND_CARD_LEVEL),
+            card_list: vec![],
+        })
+        .id();
+    commands.insert_resource(Entities { deck, card_line });
+
+    // 牌堆
+    for index in 0..20 {
+        commands.spawn((
+            Card {
+                origin: Transform::default(),
+            },
+            CardInfo {
+                name: if index % 2 == 0 {
+                    "NAAI-A-001".to_string()
+                } else {
+                    "S001-A-001".to_string()
+                },
+            },
+            DeckCard {
+                belongs_to_deck: Some(deck),
+            },
+        ));
+    }
+}
+
+fn keyboard_system(
+    input: Res<ButtonInput<KeyCode>>,
+    mut commands: Commands,
+    entities: Res<Entities>,
+    query_deck: Query<&DeckZone>,
+    query_choice: Query<(), With<CardChoice>>,
+) {
+    if input.just_pressed(KeyCode::KeyD) {
+        commands.trigger(DrawCard {
+            deck: entities.deck,
+            to: entities.card_line,
+        });
+    }
+    if input.just_pressed(KeyCode::KeyS) {
+        commands.trigger(ShuffleDeck {
+            deck: entities.deck,
+        });
+    }
+    // 从牌堆顶的三张中选一张加入手牌
+    // 调度当前的手牌
+    for (key, rule) in [
+        (KeyCode::KeyM, MulliganRule::Free),
+        (KeyCode::KeyL, MulliganRule::London),
+    ] {
+        if input.just_pressed(key) && query_choice.is_empty() {
+            commands.spawn(Mulligan {
+                deck: entities.deck,
+                hand: entities.card_line,
+                rule,
+            });
+        }
+    }
+    if !input.just_pressed(KeyCode::KeyC) || !query_choice.is_empty() {
+        return;
+    }
+    if let Ok(deck) = query_deck.get(entities.deck) {
+        let cards = deck.card_list.iter().rev().take(3).copied().collect();
+        commands.spawn((CardChoice::one_of(cards), Discover));
+    }
+}
+
+fn mulligan_finished(mulligan_finished: Trigger<MulliganFinished>) {
+    info!("{:?}", mulligan_finished.event());
+}
+
+fn choice_made(
+    choice_made: Trigger<ChoiceMade>,
+    mut commands: Commands,
+    entities: Res<Entities>,
+    query_discover: Query<(), With<Discover>>,
+) {
+    info!("{:?}", choice_made.event());
+    if !query_discover.contains(choice_made.choice) {
+        return;
+    }
+    for card in choice_made.chosen.iter() {
+        send_card_to(&mut commands, *card, entities.card_line);
+    }
+}
+
+fn card_drawn(card_drawn: Trigger<CardDrawn>) {
+    info!("{:?}", card_drawn.event());
+}
+
+#[derive(Component, Clone)]
+struct DeckInfo;
+
+/// 从牌堆顶发现一张卡片
+#[derive(Component)]
+struct Discover;
+
+impl ZoneMaterialGetter for DeckInfo {
+    fn get_mal(
+        &self,
+        materials: &mut ResMut<Assets<StandardMaterial>>,
+        _asset_server: &Res<AssetServer>,
+    ) -> Handle<StandardMaterial> {
+        materials.add(Color::Srgba(DARK_SLATE_GRAY))
+    }
+}
@@ -2,9 +2,11 @@ mod helpers;
 
 use bevy::color::palettes::css::RED;
 use bevy::prelude::*;
-use bevy_card3d_kit::prelude::card_state::CardState;
-use bevy_card3d_kit::prelude::{Card, Card3DPlugins, HAND_CARD_LEVEL, SharkCamera};
-use bevy_card3d_kit::zone::desk_zone::{DeskCard, DeskZone};
+use bevy_card3d_kit::prelude::card_state::{CardOrientation, CardState};
+use bevy_card3d_kit::prelude::{Card, Card3DPlugins, HAND_CARD_LEVEL, Moveable, SharkCamera};
+use bevy_card3d_kit::zone::desk_zone::{DeskCard, DeskZone, ZoneOverflow};
+use bevy_card3d_kit::zone::events::{CardOnZone, ZoneRejected};
+use bevy_card3d_kit::zone::label::ZoneLabel;
 use bevy_card3d_kit::zone::{Zone, ZoneMaterialGetter, bind_zone_render};
 use bevy_inspector_egui::bevy_egui::EguiPlugin;
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
@@ -19,6 +21,8 @@ fn main() {
         .add_plugins(WorldInspectorPlugin::new())
         .add_systems(Startup, setup)
         .add_systems(Update, spacebar_system)
+        .add_observer(card_on_zone)
+        .add_observer(zone_rejected)
         .add_plugins(|app: &mut App| {
             bind_zone_render::<ZoneInfo>(app);
         })
@@ -48,7 +52,7 @@ fn setup(mut commands: Commands) {
     ));
 
     commands.spawn((
-        Text::new("Press `Space` to create a Card\nAnd Click to back to Desk Zone."),
+        Text::new("Press `Space` to create a Card\nAnd Click or Drag it to back to Desk Zone."),
         Node {
             position_type: PositionType::Absolute,
             bottom: Val::Px(12.0),
@@ -63,11 +67,16 @@ fn setup(mut commands: Commands) {
                 center: Transform::from_xyz(15., -5., 0.0),
                 size: Vec2::new(3.7, 5.),
             },
-            DeskZone::default(),
+            DeskZone {
+                opt_capacity: Some(5),
+                overflow: ZoneOverflow::Reject,
+                ..default()
+            },
             ZoneInfo::Desk,
+            ZoneLabel::new("Desk: {count}"),
             CardState {
                 face_up: false,
-                vertical: true,
+                orientation: CardOrientation::Upright,
             },
         ))
         .id();
@@ -99,11 +108,26 @@ fn spacebar_system(input: Res<ButtonInput<KeyCode>>, mut commands: Commands) {
                 Card {
                     origin: Transform::from_xyz(0.0, 0.0, HAND_CARD_LEVEL),
                 },
+                Moveable,
             ))
             .observe(observer_click);
     }
 }
 
+fn card_on_zone(card_on_zone: Trigger<CardOnZone>, mut commands: Commands) {
+    commands
+        .entity(card_on_zone.card)
+        .remove::<Moveable>()
+        .insert(DeskCard {
+            belongs_to_desk: Some(card_on_zone.zone),
+        });
+}
+
+// 场地满了 卡片会回到原处
+fn zone_rejected(zone_rejected: Trigger<ZoneRejected>) {
+    info!("{:?}", zone_rejected.reason);
+}
+
 fn observer_click(
     click: Trigger<Pointer<Click>>,
     mut commands: Commands,
@@ -5,7 +5,7 @@ use bevy::math::Vec3;
 use bevy::pbr::PointLight;
 use bevy::prelude::*;
 use bevy_inspector_egui::bevy_egui::EguiPlugin;
-use bevy_card3d_kit::prelude::card_state::CardState;
+use bevy_card3d_kit::prelude::card_state::{CardOrientation, CardState};
 use bevy_card3d_kit::prelude::{
     Card, Card3DPlugins, CardLine, HAND_CARD_LEVEL, HandCard, Moveable, SharkCamera,
 };
@@ -52,7 +52,7 @@ fn setup(mut commands: Commands) {
             },
             // CardState {
             //     face_up: false,
-            //     vertical: true,
+            //     orientation: CardOrientation::Upright,
             // },
         ))
         .id();
@@ -89,9 +89,10 @@ fn setup(mut commands: Commands) {
                 transform: Transform::from_xyz(0.0, 6.7, HAND_CARD_LEVEL),
                 card_list: vec![],
             },
+            // 朝向对手
             CardState {
                 face_up: false,
-                vertical: true,
+                orientation: CardOrientation::UpsideDown,
             },
         ))
         .id();
@@ -0,0 +1,106 @@
+mod helpers;
+
+use bevy::prelude::*;
+use bevy_card3d_kit::prelude::{Card, Card3DPlugins, SharkCamera};
+use bevy_card3d_kit::targeting::{
+    TargetChosen, TargetFilter, TargetingArrow, TargetingCancelled, bind_target_filter,
+};
+use bevy_card3d_kit::tween::card_crack::CardCrack;
+use helpers::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, Card3DPlugins, SimplePlugin))
+        .add_systems(Startup, setup)
+        .add_observer(target_chosen)
+        .add_observer(targeting_cancelled)
+        .add_plugins(|app: &mut App| {
+            bind_target_filter::<Attacker, Enemy>(app);
+        })
+        .run();
+}
+
+/// 发起攻击的卡片
+#[derive(Component, Clone)]
+struct Attacker;
+
+/// 可以被攻击的卡片
+#[derive(Component, Clone)]
+struct Enemy;
+
+impl TargetFilter<Enemy> for Attacker {
+    fn can_target(&self, _target: &Enemy) -> bool {
+        true
+    }
+}
+
+fn setup(mut commands: Commands) {
+    // 相机
+    commands.spawn((
+        SharkCamera,
+        Camera3d::default(),
+        Transform::from_xyz(0., 0., 25.).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    // 光源
+    commands.spawn((
+        PointLight {
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, 10.0),
+    ));
+
+    commands.spawn((
+        Text::new("Click the bottom card to attack\nRight click or `Esc` to cancel."),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+    ));
+
+    commands
+        .spawn((
+            CardInfo {
+                name: "NAAI-A-001".to_string(),
+            },
+            Card {
+                origin: Transform::from_xyz(0.0, -4.0, 0.0),
+            },
+            Attacker,
+        ))
+        .observe(start_targeting);
+
+    for x in [-4.0, 0.0, 4.0] {
+        commands.spawn((
+            CardInfo {
+                name: "S001-A-001".to_string(),
+            },
+            Card {
+                origin: Transform::from_xyz(x, 4.0, 0.0),
+            },
+            Enemy,
+        ));
+    }
+}
+
+fn start_targeting(
+    click: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    query: Query<(), With<TargetingArrow>>,
+) {
+    if query.is_empty() {
+        commands.spawn(TargetingArrow::new(click.target()));
+    }
+}
+
+fn target_chosen(chosen: Trigger<TargetChosen>, mut commands: Commands) {
+    info!("{:?}", chosen.event());
+    commands.entity(chosen.target).insert(CardCrack);
+}
+
+fn targeting_cancelled(cancelled: Trigger<TargetingCancelled>) {
+    info!("{:?}", cancelled.event());
+}
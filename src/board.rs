@@ -0,0 +1,470 @@
+use crate::card::card_state::CardState;
+use crate::prelude::{CardLine, HandCardChanged};
+use crate::zone::deck_zone::{DeckZone, relayout_deck};
+use crate::zone::desk_zone::{DeskZone, DeskZoneChangedEvent, ZoneOverflow};
+use crate::zone::label::ZoneLabel;
+use crate::zone::layout::ZoneLayout;
+use crate::zone::shape::ZoneShape;
+use crate::zone::visibility::{PlayerId, ZoneOwner, ZoneVisibility};
+use crate::zone::{Zone, ZoneMaterialGetter, bind_zone_render};
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::fmt::{Display, Formatter};
+
+/// 牌桌布局 从 `.board.ron` 文件加载
+///
+/// 添加 [`Board`] 后按照布局生成场地和手牌，文件修改后原地更新
+#[derive(Asset, TypePath, Clone, Debug, Default, Deserialize)]
+pub struct BoardLayout {
+    #[serde(default)]
+    pub zones: Vec<BoardZone>,
+    #[serde(default)]
+    pub hands: Vec<BoardHand>,
+}
+
+/// 布局中的场地
+#[derive(Clone, Debug, Deserialize)]
+pub struct BoardZone {
+    /// 布局内唯一的名字 热重载时按名字对应已有的场地
+    pub name: String,
+    pub position: Vec3,
+    /// 绕 z 轴旋转的角度(度)
+    #[serde(default)]
+    pub rotation: f32,
+    pub size: Vec2,
+    #[serde(default)]
+    pub shape: ZoneShape,
+    #[serde(default = "default_zone_color")]
+    pub color: Srgba,
+    #[serde(default)]
+    pub kind: BoardZoneKind,
+    /// 场地中卡片的默认姿态
+    #[serde(default)]
+    pub card_state: Option<CardState>,
+    #[serde(default)]
+    pub owner: Option<PlayerId>,
+    #[serde(default)]
+    pub visibility: ZoneVisibility,
+    /// 场地的标签 `{count}` 会替换为卡片数量
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+impl BoardZone {
+    /// 显示的标签 没有标签的牌堆显示卡片数量
+    fn zone_label(&self) -> Option<String> {
+        match &self.kind {
+            BoardZoneKind::Deck { show_count: true } if self.label.is_none() => {
+                Some("{count}".to_string())
+            }
+            _ => self.label.clone(),
+        }
+    }
+}
+
+/// 场地的种类
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+pub enum BoardZoneKind {
+    /// 只触发 CardOnZone 的普通场地
+    #[default]
+    Zone,
+    /// [`DeskZone`]
+    Desk {
+        #[serde(default)]
+        layout: ZoneLayout,
+        #[serde(default)]
+        overflow: ZoneOverflow,
+        #[serde(default)]
+        capacity: Option<usize>,
+    },
+    /// [`DeckZone`]
+    Deck {
+        #[serde(default = "default_show_count")]
+        show_count: bool,
+    },
+}
+
+/// 布局中的手牌
+#[derive(Clone, Debug, Deserialize)]
+pub struct BoardHand {
+    pub name: String,
+    pub position: Vec3,
+    #[serde(default)]
+    pub rotation: f32,
+    /// 手牌的默认姿态
+    #[serde(default)]
+    pub card_state: Option<CardState>,
+    #[serde(default)]
+    pub owner: Option<PlayerId>,
+    #[serde(default)]
+    pub visibility: ZoneVisibility,
+}
+
+fn default_zone_color() -> Srgba {
+    Srgba::gray(0.3)
+}
+
+fn default_show_count() -> bool {
+    true
+}
+
+fn board_transform(position: Vec3, rotation: f32) -> Transform {
+    Transform::from_translation(position)
+        .with_rotation(Quat::from_rotation_z(rotation.to_radians()))
+}
+
+/// 按照布局生成的牌桌 删除时一起删除生成的场地和手牌
+#[derive(Component, Clone, Debug)]
+pub struct Board(pub Handle<BoardLayout>);
+
+/// 由牌桌生成的场地或手牌
+///
+/// 可以通过 `Added<BoardPart>` 按名字给场地添加自己的组件
+#[derive(Component, Clone, Debug)]
+pub struct BoardPart {
+    pub board: Entity,
+    pub name: String,
+}
+
+/// 牌桌场地的颜色
+#[derive(Component, Clone, Copy, Debug)]
+pub struct BoardZoneColor(pub Color);
+
+impl ZoneMaterialGetter for BoardZoneColor {
+    fn get_mal(
+        &self,
+        materials: &mut ResMut<Assets<StandardMaterial>>,
+        _asset_server: &Res<AssetServer>,
+    ) -> Handle<StandardMaterial> {
+        materials.add(self.0)
+    }
+}
+
+/// 加载布局失败
+#[derive(Debug)]
+pub enum BoardLayoutLoaderError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+}
+
+impl Display for BoardLayoutLoaderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BoardLayoutLoaderError::Io(err) => write!(f, "could not read board layout: {err}"),
+            BoardLayoutLoaderError::Ron(err) => write!(f, "could not parse board layout: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for BoardLayoutLoaderError {}
+
+impl From<std::io::Error> for BoardLayoutLoaderError {
+    fn from(err: std::io::Error) -> Self {
+        BoardLayoutLoaderError::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for BoardLayoutLoaderError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        BoardLayoutLoaderError::Ron(err)
+    }
+}
+
+/// 只加载 `.board.ron` 文件 暂不支持 JSON
+#[derive(Default)]
+pub struct BoardLayoutLoader;
+
+impl AssetLoader for BoardLayoutLoader {
+    type Asset = BoardLayout;
+    type Settings = ();
+    type Error = BoardLayoutLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["board.ron"]
+    }
+}
+
+pub struct BoardPlugin;
+
+impl Plugin for BoardPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<BoardLayout>()
+            .init_asset_loader::<BoardLayoutLoader>()
+            .add_observer(despawn_board)
+            .add_systems(Update, build_board);
+        bind_zone_render::<BoardZoneColor>(app);
+    }
+}
+
+/// 布局加载或修改后 生成或更新牌桌
+fn build_board(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<BoardLayout>>,
+    layouts: Res<Assets<BoardLayout>>,
+    query_board: Query<(Entity, &Board)>,
+    query_added: Query<Entity, Added<Board>>,
+) {
+    let changed: HashSet<AssetId<BoardLayout>> = events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+    for (board_entity, board) in query_board.iter() {
+        if !changed.contains(&board.0.id()) && !query_added.contains(board_entity) {
+            continue;
+        }
+        let Some(layout) = layouts.get(&board.0) else {
+            continue;
+        };
+        let layout = layout.clone();
+        commands.queue(move |world: &mut World| sync_board(world, board_entity, layout));
+    }
+}
+
+/// 按名字对应已有的场地和手牌 更新、生成或删除
+fn sync_board(world: &mut World, board_entity: Entity, layout: BoardLayout) {
+    let mut parts: HashMap<String, Entity> = world
+        .query::<(Entity, &BoardPart)>()
+        .iter(world)
+        .filter(|(_, part)| part.board == board_entity)
+        .map(|(entity, part)| (part.name.clone(), entity))
+        .collect();
+
+    for desc in layout.zones {
+        match parts.remove(&desc.name) {
+            Some(entity) if same_kind(world, entity, &desc.kind) => {
+                update_zone(world, entity, desc);
+            }
+            opt_entity => {
+                if let Some(entity) = opt_entity {
+                    world.entity_mut(entity).despawn();
+                }
+                spawn_zone(world, board_entity, desc);
+            }
+        }
+    }
+
+    for desc in layout.hands {
+        let transform = board_transform(desc.position, desc.rotation);
+        let entity = match parts.remove(&desc.name) {
+            Some(entity) if world.get::<CardLine>(entity).is_some() => {
+                world.get_mut::<CardLine>(entity).unwrap().transform = transform;
+                world.send_event(HandCardChanged::Relayout {
+                    card_line_entity: entity,
+                });
+                entity
+            }
+            opt_entity => {
+                if let Some(entity) = opt_entity {
+                    world.entity_mut(entity).despawn();
+                }
+                world
+                    .spawn((
+                        Name::new(desc.name.clone()),
+                        BoardPart {
+                            board: board_entity,
+                            name: desc.name,
+                        },
+                        CardLine {
+                            transform,
+                            card_list: vec![],
+                        },
+                    ))
+                    .id()
+            }
+        };
+        set_card_state(world, entity, desc.card_state);
+        set_owner(world, entity, desc.owner, desc.visibility);
+    }
+
+    // 布局中删掉的部分
+    for entity in parts.into_values() {
+        world.entity_mut(entity).despawn();
+    }
+}
+
+fn same_kind(world: &World, entity: Entity, kind: &BoardZoneKind) -> bool {
+    if world.get::<Zone>(entity).is_none() {
+        return false;
+    }
+    let desk = world.get::<DeskZone>(entity).is_some();
+    let deck = world.get::<DeckZone>(entity).is_some();
+    match kind {
+        BoardZoneKind::Zone => !desk && !deck,
+        BoardZoneKind::Desk { .. } => desk,
+        BoardZoneKind::Deck { .. } => deck,
+    }
+}
+
+fn spawn_zone(world: &mut World, board_entity: Entity, desc: BoardZone) {
+    let label = desc.zone_label();
+    let mut entity = world.spawn((
+        Name::new(desc.name.clone()),
+        BoardPart {
+            board: board_entity,
+            name: desc.name.clone(),
+        },
+        Zone {
+            center: board_transform(desc.position, desc.rotation),
+            size: desc.size,
+        },
+        desc.shape,
+        BoardZoneColor(desc.color.into()),
+    ));
+    match desc.kind {
+        BoardZoneKind::Zone => {}
+        BoardZoneKind::Desk {
+            layout,
+            overflow,
+            capacity,
+        } => {
+            entity.insert(DeskZone {
+                card_list: vec![],
+                opt_capacity: capacity,
+                layout,
+                overflow,
+            });
+        }
+        BoardZoneKind::Deck { show_count } => {
+            entity.insert(DeckZone {
+                card_list: vec![],
+                show_count,
+            });
+        }
+    }
+    if let Some(card_state) = desc.card_state {
+        entity.insert(card_state);
+    }
+    let entity = entity.id();
+    set_owner(world, entity, desc.owner, desc.visibility);
+    set_label(world, entity, label);
+}
+
+fn update_zone(world: &mut World, entity: Entity, desc: BoardZone) {
+    let label = desc.zone_label();
+    let zone = Zone {
+        center: board_transform(desc.position, desc.rotation),
+        size: desc.size,
+    };
+    let old_zone = *world.get::<Zone>(entity).unwrap();
+    let old_shape = world.get::<ZoneShape>(entity).cloned().unwrap_or_default();
+    // 大小或形状变化时重新生成网格
+    if old_zone.size != zone.size || old_shape != desc.shape {
+        let mesh = desc
+            .shape
+            .mesh(zone.size, &mut world.resource_mut::<Assets<Mesh>>());
+        world.entity_mut(entity).insert(Mesh3d(mesh));
+    }
+    let color: Color = desc.color.into();
+    if let Some(material) = world.get::<MeshMaterial3d<StandardMaterial>>(entity) {
+        let handle = material.0.clone();
+        if let Some(material) = world
+            .resource_mut::<Assets<StandardMaterial>>()
+            .get_mut(&handle)
+        {
+            material.base_color = color;
+        }
+    }
+    world
+        .entity_mut(entity)
+        .insert((zone, zone.center, desc.shape, BoardZoneColor(color)));
+    set_card_state(world, entity, desc.card_state);
+    set_owner(world, entity, desc.owner, desc.visibility);
+    set_label(world, entity, label);
+
+    match desc.kind {
+        BoardZoneKind::Zone => {}
+        BoardZoneKind::Desk {
+            layout,
+            overflow,
+            capacity,
+        } => {
+            let mut desk_zone = world.get_mut::<DeskZone>(entity).unwrap();
+            desk_zone.layout = layout;
+            desk_zone.overflow = overflow;
+            desk_zone.opt_capacity = capacity;
+            world.send_event(DeskZoneChangedEvent::Relayout { desk: entity });
+        }
+        BoardZoneKind::Deck { show_count } => {
+            let mut deck_zone = world.get_mut::<DeckZone>(entity).unwrap();
+            deck_zone.show_count = show_count;
+            let card_list = deck_zone.card_list.clone();
+            relayout_deck(world, &zone, &card_list);
+        }
+    }
+}
+
+fn set_card_state(world: &mut World, entity: Entity, opt_state: Option<CardState>) {
+    let mut entity = world.entity_mut(entity);
+    match opt_state {
+        Some(card_state) => {
+            entity.insert(card_state);
+        }
+        None => {
+            entity.remove::<CardState>();
+        }
+    }
+}
+
+fn set_owner(
+    world: &mut World,
+    entity: Entity,
+    opt_owner: Option<PlayerId>,
+    visibility: ZoneVisibility,
+) {
+    let mut entity = world.entity_mut(entity);
+    entity.insert(visibility);
+    match opt_owner {
+        Some(owner) => {
+            entity.insert(ZoneOwner(owner));
+        }
+        None => {
+            entity.remove::<ZoneOwner>();
+        }
+    }
+}
+
+fn set_label(world: &mut World, entity: Entity, opt_label: Option<String>) {
+    let mut entity = world.entity_mut(entity);
+    match (opt_label, entity.get_mut::<ZoneLabel>()) {
+        (Some(text), Some(mut label)) => {
+            if label.text != text {
+                label.text = text;
+            }
+        }
+        (Some(text), None) => {
+            entity.insert(ZoneLabel::new(text));
+        }
+        (None, _) => {
+            entity.remove::<ZoneLabel>();
+        }
+    }
+}
+
+fn despawn_board(
+    trigger: Trigger<OnRemove, Board>,
+    mut commands: Commands,
+    query_part: Query<(Entity, &BoardPart)>,
+) {
+    for (entity, part) in query_part.iter() {
+        if part.board == trigger.target() {
+            commands.entity(entity).try_despawn();
+        }
+    }
+}
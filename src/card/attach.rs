@@ -0,0 +1,241 @@
+use crate::card::card_state::{CardState, calculate_transform};
+use crate::prelude::{Card, Dragged};
+use crate::zone::send_card_to;
+use bevy::prelude::*;
+use bevy_tween::combinator::{AnimationBuilderExt, TransformTargetStateExt, parallel, tween};
+use bevy_tween::prelude::{EaseKind, IntoTarget};
+use std::time::Duration;
+
+/// 附着在另一张卡片(宿主)上 例如装备、超量素材、叠放的单位
+///
+/// 附着的卡片跟随宿主的原位移动和排列 自己的姿态不受宿主影响
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+#[relationship(relationship_target = Attachments)]
+pub struct AttachedTo(pub Entity);
+
+/// 附着在这张卡片上的卡片 按附着的先后排列
+#[derive(Component, Clone, Debug)]
+#[relationship_target(relationship = AttachedTo)]
+pub struct Attachments(Vec<Entity>);
+
+impl Attachments {
+    pub fn cards(&self) -> &[Entity] {
+        &self.0
+    }
+}
+
+/// 附着卡片相对于宿主的偏移 没有时使用 [`AttachConfig`] 中的值
+///
+/// x y 在宿主的平面内，z 为高度差，负数时在宿主下方
+#[derive(Component, Clone, Copy, Debug)]
+pub struct AttachOffset(pub Vec3);
+
+/// 宿主离开时附着卡片的去向 添加在宿主上
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub enum OnHostLeave {
+    /// 留在原地
+    #[default]
+    Detach,
+    /// 一起删除
+    Despawn,
+    /// 送到手牌、场地或牌堆
+    SendTo(Entity),
+}
+
+// 宿主离开 按照宿主的 OnHostLeave 处理附着的卡片
+// 宿主被删除、通过 send_card_to 离开或者从 DeskZone 中移除时会自动触发
+#[derive(Event, Clone, Debug)]
+pub struct HostLeave {
+    pub host: Entity,
+}
+
+/// 附着的默认样式
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct AttachConfig {
+    /// 每张附着卡片依次叠加的偏移
+    pub offset: Vec3,
+    /// 跟随宿主移动的时长
+    pub follow_duration: f32,
+}
+
+impl Default for AttachConfig {
+    fn default() -> Self {
+        Self {
+            offset: Vec3::new(0.0, -0.6, -0.004),
+            follow_duration: 0.2,
+        }
+    }
+}
+
+pub struct AttachPlugin;
+
+impl Plugin for AttachPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AttachConfig>()
+            .add_observer(attach_card)
+            .add_observer(host_leave)
+            .add_observer(host_removed)
+            .add_systems(Update, follow_host);
+    }
+}
+
+/// 第 index 张附着卡片的位置
+pub fn attach_transform(host_transform: &Transform, offset: Vec3, index: usize) -> Transform {
+    let offset = offset * (index + 1) as f32;
+    let mut end = *host_transform;
+    end.translation += host_transform.rotation * offset.with_z(0.0) + Vec3::Z * offset.z;
+    end
+}
+
+/// 附着时设置卡片的原位 拖拽放下的卡片会直接回到附着的位置
+fn attach_card(
+    trigger: Trigger<OnInsert, AttachedTo>,
+    mut commands: Commands,
+    config: Res<AttachConfig>,
+    query_attached: Query<(&AttachedTo, Option<&AttachOffset>)>,
+    query_host: Query<&Attachments>,
+    mut query_card: Query<(&mut Card, &Transform, Option<&CardState>)>,
+    query_dragged: Query<(), With<Dragged>>,
+) {
+    let card_entity = trigger.target();
+    let Ok((attached_to, opt_offset)) = query_attached.get(card_entity) else {
+        return;
+    };
+    let (Ok(attachments), Ok((host, _, _))) =
+        (query_host.get(attached_to.0), query_card.get(attached_to.0))
+    else {
+        return;
+    };
+    let index = attachments
+        .iter()
+        .position(|x| x == card_entity)
+        .unwrap_or_default();
+    let end = attach_transform(
+        &host.origin,
+        opt_offset.map_or(config.offset, |offset| offset.0),
+        index,
+    );
+    if let Ok((mut card, transform, opt_state)) = query_card.get_mut(card_entity) {
+        card.origin = end;
+        if !query_dragged.contains(card_entity) {
+            play_follow_host_animation(
+                card_entity,
+                transform,
+                calculate_transform(end, opt_state.cloned()),
+                &config,
+                &mut commands,
+            );
+        }
+    }
+}
+
+/// 宿主的原位变化时 附着卡片移动到新的位置
+///
+/// 附着在附着卡片上的卡片同样会跟随
+fn follow_host(
+    mut commands: Commands,
+    config: Res<AttachConfig>,
+    query_host: Query<(Entity, &Attachments)>,
+    mut query_card: Query<(&mut Card, &Transform, Option<&CardState>)>,
+    query_dragged: Query<(), With<Dragged>>,
+    query_offset: Query<&AttachOffset>,
+) {
+    for (host_entity, attachments) in query_host.iter() {
+        let Ok((host, _, _)) = query_card.get(host_entity) else {
+            continue;
+        };
+        let host_origin = host.origin;
+        for (index, card_entity) in attachments.iter().enumerate() {
+            // 拖拽中的卡片不跟随
+            if query_dragged.contains(card_entity) {
+                continue;
+            }
+            let Ok((mut card, transform, opt_state)) = query_card.get_mut(card_entity) else {
+                continue;
+            };
+            let offset = query_offset
+                .get(card_entity)
+                .map_or(config.offset, |offset| offset.0);
+            let end = attach_transform(&host_origin, offset, index);
+            if card.origin == end {
+                continue;
+            }
+            card.origin = end;
+            play_follow_host_animation(
+                card_entity,
+                transform,
+                calculate_transform(end, opt_state.cloned()),
+                &config,
+                &mut commands,
+            );
+        }
+    }
+}
+
+fn play_follow_host_animation(
+    card_entity: Entity,
+    card_transform: &Transform,
+    end: Transform,
+    config: &AttachConfig,
+    commands: &mut Commands,
+) {
+    let target = card_entity.into_target();
+    let mut start = target.transform_state(*card_transform);
+    let duration = Duration::from_secs_f32(config.follow_duration);
+    commands
+        .spawn(Name::new(format!("follow host {:?}", card_entity)))
+        .animation()
+        .insert(parallel((
+            tween(
+                duration,
+                EaseKind::ExponentialOut,
+                start.translation_to(end.translation),
+            ),
+            tween(
+                duration,
+                EaseKind::ExponentialOut,
+                start.rotation_to(end.rotation),
+            ),
+            tween(
+                duration,
+                EaseKind::ExponentialOut,
+                start.scale_to(end.scale),
+            ),
+        )));
+}
+
+fn host_leave(
+    trigger: Trigger<HostLeave>,
+    mut commands: Commands,
+    query_host: Query<(&Attachments, Option<&OnHostLeave>)>,
+) {
+    if let Ok((attachments, opt_policy)) = query_host.get(trigger.host) {
+        for card in attachments.iter() {
+            commands.entity(card).remove::<AttachedTo>();
+            apply_host_leave(&mut commands, card, opt_policy.copied().unwrap_or_default());
+        }
+    }
+}
+
+/// 宿主被删除
+fn host_removed(
+    trigger: Trigger<OnRemove, Attachments>,
+    mut commands: Commands,
+    query_host: Query<(&Attachments, Option<&OnHostLeave>)>,
+) {
+    if let Ok((attachments, opt_policy)) = query_host.get(trigger.target()) {
+        for card in attachments.iter() {
+            apply_host_leave(&mut commands, card, opt_policy.copied().unwrap_or_default());
+        }
+    }
+}
+
+fn apply_host_leave(commands: &mut Commands, card: Entity, policy: OnHostLeave) {
+    match policy {
+        OnHostLeave::Detach => {}
+        OnHostLeave::Despawn => {
+            commands.entity(card).try_despawn();
+        }
+        OnHostLeave::SendTo(to) => send_card_to(commands, card, to),
+    }
+}
@@ -1,18 +1,86 @@
-use crate::prelude::Card;
+use crate::prelude::event::DeclareStateChangeDoneForCard;
+use crate::prelude::{Card, Dragged};
 use bevy::prelude::*;
-use bevy_tween::combinator::{TransformTargetStateExt, sequence, tween};
-use bevy_tween::prelude::{AnimationBuilderExt, EaseKind, IntoTarget};
+use bevy_tween::combinator::{TransformTargetStateExt, event, parallel, sequence, tween};
+use bevy_tween::prelude::{AnimationBuilderExt, EaseKind, IntoTarget, TweenEvent};
+use serde::Deserialize;
+use std::collections::VecDeque;
 use std::f32::consts::PI;
 use std::time::Duration;
 
 /// 卡片姿态信息
-#[derive(Debug, PartialEq, Eq, Clone, Component, Reflect)]
+#[derive(Debug, PartialEq, Eq, Clone, Component, Reflect, Deserialize)]
 #[reflect(Component)]
 pub struct CardState {
     /// 正面朝上
     pub face_up: bool,
-    /// 是否竖直
-    pub vertical: bool,
+    /// 朝向
+    #[serde(default)]
+    pub orientation: CardOrientation,
+}
+
+/// 卡片的朝向 从上方看逆时针旋转
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Reflect, Deserialize)]
+pub enum CardOrientation {
+    /// 竖直
+    #[default]
+    Upright,
+    /// 横置 逆时针转 90°
+    Sideways,
+    /// 倒置 例如朝向对手的卡片
+    UpsideDown,
+    /// 顺时针转 90°
+    SidewaysReversed,
+    /// 稍微倾斜 单位为度 例如表示疲劳
+    Tilted(i16),
+}
+
+impl CardOrientation {
+    /// 疲劳时的倾斜
+    pub const EXHAUSTED: CardOrientation = CardOrientation::Tilted(15);
+
+    /// 逆时针转 turns 个 90°
+    pub fn quarter_turns(turns: i32) -> Self {
+        match turns.rem_euclid(4) {
+            0 => CardOrientation::Upright,
+            1 => CardOrientation::Sideways,
+            2 => CardOrientation::UpsideDown,
+            _ => CardOrientation::SidewaysReversed,
+        }
+    }
+
+    /// 绕 z 轴旋转的弧度
+    pub fn angle(&self) -> f32 {
+        match self {
+            CardOrientation::Upright => 0.0,
+            CardOrientation::Sideways => PI / 2.0,
+            CardOrientation::UpsideDown => PI,
+            CardOrientation::SidewaysReversed => -PI / 2.0,
+            CardOrientation::Tilted(degrees) => (*degrees as f32).to_radians(),
+        }
+    }
+
+    /// 是否横着放 宽和高互换
+    pub fn is_sideways(&self) -> bool {
+        matches!(
+            self,
+            CardOrientation::Sideways | CardOrientation::SidewaysReversed
+        )
+    }
+
+    /// 旋转后卡片在桌面上占用的大小 `size` 为竖直时的宽和高
+    pub fn footprint(&self, size: Vec2) -> Vec2 {
+        match self {
+            // 倾斜的卡片占用旋转后的外接矩形
+            CardOrientation::Tilted(_) => {
+                let (sin, cos) = self.angle().sin_cos();
+                let (sin, cos) = (sin.abs(), cos.abs());
+                Vec2::new(size.x * cos + size.y * sin, size.x * sin + size.y * cos)
+            }
+            _ if self.is_sideways() => size.yx(),
+            _ => size,
+        }
+    }
 }
 
 /// 计算姿态影响下的值
@@ -22,8 +90,11 @@ pub fn calculate_transform(trans: Transform, opt_card_state: Option<CardState>)
         if !card_state.face_up {
             res.rotate(Quat::from_axis_angle(Vec3::Y, PI));
         }
-        if !card_state.vertical {
-            res.rotate(Quat::from_axis_angle(Vec3::Z, PI / 2.0));
+        if card_state.orientation != CardOrientation::Upright {
+            res.rotate(Quat::from_axis_angle(
+                Vec3::Z,
+                card_state.orientation.angle(),
+            ));
         }
     }
     res
@@ -33,59 +104,334 @@ pub fn calculate_transform(trans: Transform, opt_card_state: Option<CardState>)
 #[reflect(Component)]
 pub struct ChangeCardState(pub CardState);
 
+/// 姿态变化的动画
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct CardFlipConfig {
+    /// 翻面的时长
+    pub flip_duration: f32,
+    /// 翻面时抬起的高度 不小于卡片宽度的一半时不会穿过桌面
+    pub flip_height: f32,
+    /// 翻面时的旋转
+    pub flip_ease: EaseKind,
+    /// 翻面前半段抬起
+    pub lift_ease: EaseKind,
+    /// 翻面后半段落下
+    pub drop_ease: EaseKind,
+    /// 只改变朝向时原地旋转的时长
+    pub tap_duration: f32,
+    pub tap_ease: EaseKind,
+    /// 合并排队中多余的变化 例如连续翻两次面
+    pub coalesce: bool,
+}
+
+impl Default for CardFlipConfig {
+    fn default() -> Self {
+        Self {
+            flip_duration: 0.5,
+            flip_height: 2.0,
+            flip_ease: EaseKind::CubicInOut,
+            lift_ease: EaseKind::QuadraticOut,
+            drop_ease: EaseKind::QuadraticIn,
+            tap_duration: 0.25,
+            tap_ease: EaseKind::QuadraticOut,
+            coalesce: true,
+        }
+    }
+}
+
+/// 卡片上等待播放的姿态变化
+///
+/// 动画播放时插入的 ChangeCardState 会排队 上一个动画结束后从卡片当前的位置继续播放
+///
+/// 卡片被拖拽时也会等待 拖拽结束后再播放
+#[derive(Component, Clone, Debug, Default)]
+pub struct CardStateQueue {
+    pending: VecDeque<CardState>,
+    animation: Option<Entity>,
+    /// 正在播放的动画开始前的姿态
+    from: Option<CardState>,
+}
+
+impl CardStateQueue {
+    /// 还未开始的姿态变化
+    pub fn pending(&self) -> impl Iterator<Item = &CardState> {
+        self.pending.iter()
+    }
+
+    /// 最后会变成的姿态
+    fn latest<'a>(&'a self, current: &'a CardState) -> &'a CardState {
+        self.pending.back().unwrap_or(current)
+    }
+}
+
+/// 卡片的姿态被修改 触发在卡片实体上
+#[derive(Event, Clone, Debug)]
+pub struct CardStateChanged {
+    pub old: CardState,
+    pub new: CardState,
+}
+
+/// 卡片的姿态动画结束 触发在卡片实体上
+///
+/// 翻面和原地旋转都会触发 动画被其他动画打断时也会触发
+#[derive(Event, Clone, Debug)]
+pub struct OnFlipFinished {
+    pub old: CardState,
+    pub new: CardState,
+}
+
 pub struct CardStatePlugin;
 
 impl Plugin for CardStatePlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<ChangeCardState>();
-        app.add_systems(Update, on_update_change_state);
+        app.init_resource::<CardFlipConfig>();
+        app.add_observer(queue_change_state)
+            .add_observer(listen_to_state_change_done_for_card);
+        app.add_systems(Update, play_queued_change_state);
     }
 }
 
-fn on_update_change_state(
+/// 每次插入都会触发 同一帧内多次修改也不会丢失
+fn queue_change_state(
+    trigger: Trigger<OnInsert, ChangeCardState>,
     mut commands: Commands,
-    query: Query<(Entity, &Card, &CardState, &ChangeCardState), Added<ChangeCardState>>,
+    config: Res<CardFlipConfig>,
+    mut query: Query<(&ChangeCardState, &CardState, Option<&mut CardStateQueue>)>,
 ) {
-    query
-        .iter()
-        .for_each(|(entity, card, card_state, change_card_state)| {
-            let start_tr = calculate_transform(card.origin, Some(card_state.clone()));
-            let end_tr = calculate_transform(card.origin, Some(change_card_state.0.clone()));
-
-            let target = entity.clone().into_target();
-            let mut start = target.transform_state(start_tr);
-
-            commands
-                .entity(entity)
-                .insert(change_card_state.0.clone())
-                .remove::<ChangeCardState>();
-            info!("state from {:?} To {:?}", card_state, change_card_state.0);
-            if change_card_state.0.face_up != card_state.face_up
-                || change_card_state.0.vertical != card_state.vertical
-            {
-                commands
-                    .spawn(Name::new(format!(
-                        "change card state {:?}",
-                        change_card_state
-                    )))
-                    .animation()
-                    .insert(sequence((
-                        tween(
-                            Duration::from_secs_f32(0.6),
-                            EaseKind::ExponentialOut,
-                            start.translation_to(end_tr.translation),
-                        ),
-                        tween(
-                            Duration::from_secs_f32(0.6),
-                            EaseKind::ExponentialOut,
-                            start.rotation_to(end_tr.rotation),
-                        ),
-                        tween(
-                            Duration::from_secs_f32(0.6),
-                            EaseKind::ExponentialOut,
-                            start.scale_to(end_tr.scale),
-                        ),
-                    )));
-            }
-        })
+    let entity = trigger.target();
+    let Ok((change_card_state, card_state, opt_queue)) = query.get_mut(entity) else {
+        return;
+    };
+    let next = change_card_state.0.clone();
+    commands.entity(entity).try_remove::<ChangeCardState>();
+    let Some(mut queue) = opt_queue else {
+        commands.entity(entity).insert(CardStateQueue {
+            pending: VecDeque::from([next]),
+            animation: None,
+            from: None,
+        });
+        return;
+    };
+    if config.coalesce {
+        if *queue.latest(card_state) == next {
+            return;
+        }
+        // 改回上一次的姿态 两次变化互相抵消
+        let before_latest = match queue.pending.len() {
+            0 => None,
+            1 => Some(card_state),
+            len => queue.pending.get(len - 2),
+        };
+        if before_latest == Some(&next) {
+            queue.pending.pop_back();
+            return;
+        }
+    }
+    queue.pending.push_back(next);
+}
+
+fn play_queued_change_state(
+    mut commands: Commands,
+    config: Res<CardFlipConfig>,
+    mut query: Query<(Entity, &Card, &Transform, &CardState, &mut CardStateQueue)>,
+    query_alive: Query<()>,
+    query_dragged: Query<(), With<Dragged>>,
+) {
+    for (entity, card, transform, card_state, mut queue) in query.iter_mut() {
+        if queue
+            .animation
+            .is_some_and(|animation| query_alive.get(animation).is_ok())
+        {
+            continue;
+        }
+        queue.animation = None;
+        // 动画被打断 没有收到结束的事件
+        if let Some(old) = queue.from.take() {
+            commands.trigger_targets(
+                OnFlipFinished {
+                    old,
+                    new: card_state.clone(),
+                },
+                entity,
+            );
+        }
+        // 拖拽和拖拽后回到原位时不打断 结束后再播放
+        if query_dragged.contains(entity) {
+            continue;
+        }
+        let Some(next) = queue.pending.pop_front() else {
+            continue;
+        };
+        commands.entity(entity).insert(next.clone());
+        info!("state from {:?} To {:?}", card_state, next);
+        if next == *card_state {
+            continue;
+        }
+        commands.trigger_targets(
+            CardStateChanged {
+                old: card_state.clone(),
+                new: next.clone(),
+            },
+            entity,
+        );
+        // 从当前的位置开始
+        let end_tr = calculate_transform(card.origin, Some(next.clone()));
+        let name = Name::new(format!("change card state {:?}", next));
+        let animation = if next.face_up != card_state.face_up {
+            play_card_flip_animation(entity, *transform, end_tr, name, &config, &mut commands)
+        } else {
+            play_card_tap_animation(entity, *transform, end_tr, name, &config, &mut commands)
+        };
+        queue.animation = Some(animation);
+        queue.from = Some(card_state.clone());
+    }
+}
+
+fn listen_to_state_change_done_for_card(
+    trigger: Trigger<TweenEvent<DeclareStateChangeDoneForCard>>,
+    mut commands: Commands,
+    mut query: Query<(&CardState, &mut CardStateQueue)>,
+) {
+    let Some(entity) = trigger.data.card_entity else {
+        return;
+    };
+    let Ok((card_state, mut queue)) = query.get_mut(entity) else {
+        return;
+    };
+    if let Some(old) = queue.from.take() {
+        commands.trigger_targets(
+            OnFlipFinished {
+                old,
+                new: card_state.clone(),
+            },
+            entity,
+        );
+    }
+}
+
+/// 翻面 抬起后绕经过中心的长轴翻转再落下
+pub fn play_card_flip_animation(
+    entity: Entity,
+    start_tr: Transform,
+    end_tr: Transform,
+    name: Name,
+    config: &CardFlipConfig,
+    commands: &mut Commands,
+) -> Entity {
+    let target = entity.into_target();
+    let mut start = target.transform_state(start_tr);
+    let half = Duration::from_secs_f32(config.flip_duration / 2.0);
+    let top = start_tr.translation.lerp(end_tr.translation, 0.5) + Vec3::Z * config.flip_height;
+    commands
+        .spawn(name)
+        .animation()
+        .insert(sequence((
+            parallel((
+                sequence((
+                    tween(half, config.lift_ease, start.translation_to(top)),
+                    tween(
+                        half,
+                        config.drop_ease,
+                        start.translation_to(end_tr.translation),
+                    ),
+                )),
+                tween(
+                    half * 2,
+                    config.flip_ease,
+                    start.rotation_to(end_tr.rotation),
+                ),
+                tween(half * 2, config.flip_ease, start.scale_to(end_tr.scale)),
+            )),
+            event(DeclareStateChangeDoneForCard {
+                card_entity: Some(entity),
+            }),
+        )))
+        .id()
+}
+
+/// 横置或竖直 原地旋转
+pub fn play_card_tap_animation(
+    entity: Entity,
+    start_tr: Transform,
+    end_tr: Transform,
+    name: Name,
+    config: &CardFlipConfig,
+    commands: &mut Commands,
+) -> Entity {
+    let target = entity.into_target();
+    let mut start = target.transform_state(start_tr);
+    let duration = Duration::from_secs_f32(config.tap_duration);
+    commands
+        .spawn(name)
+        .animation()
+        .insert(sequence((
+            parallel((
+                tween(
+                    duration,
+                    config.tap_ease,
+                    start.translation_to(end_tr.translation),
+                ),
+                tween(
+                    duration,
+                    config.tap_ease,
+                    start.rotation_to(end_tr.rotation),
+                ),
+                tween(duration, config.tap_ease, start.scale_to(end_tr.scale)),
+            )),
+            event(DeclareStateChangeDoneForCard {
+                card_entity: Some(entity),
+            }),
+        )))
+        .id()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quarter_turns_wraps_negative_and_large_input() {
+        assert_eq!(CardOrientation::quarter_turns(0), CardOrientation::Upright);
+        assert_eq!(CardOrientation::quarter_turns(1), CardOrientation::Sideways);
+        assert_eq!(
+            CardOrientation::quarter_turns(-1),
+            CardOrientation::SidewaysReversed
+        );
+        assert_eq!(
+            CardOrientation::quarter_turns(-2),
+            CardOrientation::UpsideDown
+        );
+        assert_eq!(CardOrientation::quarter_turns(-4), CardOrientation::Upright);
+        assert_eq!(CardOrientation::quarter_turns(5), CardOrientation::Sideways);
+        assert_eq!(
+            CardOrientation::quarter_turns(i32::MIN),
+            CardOrientation::Upright
+        );
+    }
+
+    #[test]
+    fn sideways_footprint_swaps_width_and_height() {
+        let size = Vec2::new(2.0, 3.0);
+        assert_eq!(CardOrientation::Upright.footprint(size), size);
+        assert_eq!(CardOrientation::UpsideDown.footprint(size), size);
+        assert_eq!(
+            CardOrientation::Sideways.footprint(size),
+            Vec2::new(3.0, 2.0)
+        );
+        assert_eq!(
+            CardOrientation::SidewaysReversed.footprint(size),
+            Vec2::new(3.0, 2.0)
+        );
+        assert!(CardOrientation::quarter_turns(3).is_sideways());
+    }
+
+    #[test]
+    fn tilted_footprint_is_bounding_box() {
+        let size = Vec2::new(2.0, 3.0);
+        let exhausted = CardOrientation::EXHAUSTED.footprint(size);
+        assert!(exhausted.x > size.x && exhausted.y > size.y);
+        let turned = CardOrientation::Tilted(90).footprint(size);
+        assert!(turned.abs_diff_eq(Vec2::new(3.0, 2.0), 1e-5));
+    }
 }
@@ -32,6 +32,21 @@ pub enum Dragged {
 /// A tag added to hovered cards, indicating that they're hovered over
 #[derive(Debug, Clone, Copy, Component, Default)]
 pub struct Hovered;
+
+/// 卡片正面和背面的图片
+#[derive(Component, Clone, Debug)]
+pub struct CardImages {
+    pub face: Handle<Image>,
+    pub back: Handle<Image>,
+}
+
+/// 卡片正面的网格
+#[derive(Component, Clone, Copy, Debug)]
+pub struct CardFace;
+
+/// 卡片背面的网格
+#[derive(Component, Clone, Copy, Debug)]
+pub struct CardBack;
 pub trait CardMaterialGetter {
     /// 正面素材
     fn get_face_mal(&self) -> String;
@@ -68,9 +83,14 @@ fn render_added_card<T>(
         card3d_config.thick,
     );
     for (card_entity, card, t, opt_state) in query_card.iter() {
+        let images = CardImages {
+            face: asset_server.load(t.get_face_mal()),
+            back: asset_server.load(t.get_back_mal()),
+        };
         commands
             .entity(card_entity)
             .insert(Mesh3d::default())
+            .insert(images.clone())
             // 计算新的位置
             .insert(OutlineVolume {
                 visible: false,
@@ -102,11 +122,12 @@ fn render_added_card<T>(
                         .spawn((
                             Mesh3d(mesh_handle.clone()),
                             trans.clone(),
+                            CardFace,
                             MeshMaterial3d(
                                 card_materials.add(CardMaterial {
                                     gray_scale: 0.0,
                                     crack_scale: 0.0,
-                                    base_color_texture: asset_server.load(t.get_face_mal()),
+                                    base_color_texture: images.face.clone(),
                                     crack_texture: asset_server.load(
                                         AssetPath::from(
                                             Path::new("bevy_card3d_kit")
@@ -125,10 +146,11 @@ fn render_added_card<T>(
                         .spawn((
                             Mesh3d(mesh_handle.clone()),
                             trans.clone(),
+                            CardBack,
                             MeshMaterial3d(materials.add(StandardMaterial {
                                 base_color: Color::WHITE,
                                 unlit: true,
-                                base_color_texture: Some(asset_server.load(t.get_back_mal())),
+                                base_color_texture: Some(images.back.clone()),
                                 alpha_mode: AlphaMode::Blend,
                                 ..Default::default()
                             })),
@@ -7,10 +7,17 @@ pub struct DeclareDraggingDoneForCard {
     pub card_entity: Option<Entity>,
 }
 
+/// 姿态动画结束
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize, Eq, Hash, PartialOrd, Default)]
+pub struct DeclareStateChangeDoneForCard {
+    pub card_entity: Option<Entity>,
+}
+
 pub struct CardsEventsPlugin;
 
 impl Plugin for CardsEventsPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(TweenEventPlugin::<DeclareDraggingDoneForCard>::default());
+        app.add_plugins(TweenEventPlugin::<DeclareStateChangeDoneForCard>::default());
     }
 }
@@ -1,4 +1,5 @@
 use crate::card::card_state::{CardState, calculate_transform};
+use crate::card3d::Card3DConfig;
 use crate::prelude::{Card, Dragged, Moveable};
 use crate::tween::animation::play_card_going_back_to_trans_animation;
 use bevy::math::ops::{cos, sin};
@@ -37,6 +38,8 @@ pub enum HandCardChanged {
         card_entity: Entity,
         card_line_entity: Entity,
     },
+    /// 重新排列手牌 例如修改了 CardLine 的位置
+    Relayout { card_line_entity: Entity },
 }
 
 #[derive(Resource, Copy, Clone)]
@@ -175,6 +178,8 @@ pub fn change_hand_cards_event(
     mut hand_card_changed: EventReader<HandCardChanged>,
     mut query_card_line: Query<(&mut CardLine, Option<&CardState>)>,
     mut query_card: Query<(&mut Card, &mut Transform)>,
+    query_hand_card: Query<&HandCard>,
+    card3d_config: Res<Card3DConfig>,
 ) {
     for event in hand_card_changed.read() {
         match event {
@@ -191,6 +196,7 @@ pub fn change_hand_cards_event(
                         &mut commands,
                         &mut query_card,
                         opt_state.cloned(),
+                        &card3d_config,
                     );
                 }
             }
@@ -201,12 +207,29 @@ pub fn change_hand_cards_event(
                 // 删除CardLine中的数据
                 if let Ok((mut card_line, opt_state)) = query_card_line.get_mut(*card_line_entity) {
                     card_line.card_list.retain(|x| *x != *card_entity);
-                    commands.entity(*card_entity).remove::<HandCard>();
+                    // 卡片可能已经去了别的手牌
+                    if query_hand_card.get(*card_entity).is_ok_and(|hand_card| {
+                        hand_card.belong_to_card_line == Some(*card_line_entity)
+                    }) {
+                        commands.entity(*card_entity).remove::<HandCard>();
+                    }
                     change_all_cards(
                         &card_line,
                         &mut commands,
                         &mut query_card,
                         opt_state.cloned(),
+                        &card3d_config,
+                    );
+                }
+            }
+            HandCardChanged::Relayout { card_line_entity } => {
+                if let Ok((card_line, opt_state)) = query_card_line.get(*card_line_entity) {
+                    change_all_cards(
+                        card_line,
+                        &mut commands,
+                        &mut query_card,
+                        opt_state.cloned(),
+                        &card3d_config,
                     );
                 }
             }
@@ -219,12 +242,13 @@ fn change_all_cards(
     commands: &mut Commands,
     query_card: &mut Query<(&mut Card, &mut Transform)>,
     opt_state: Option<CardState>,
+    card3d_config: &Card3DConfig,
 ) {
     if card_line.card_list.len() == 0 {
         return;
     }
     // 计算所有卡的位置
-    let hand_positions = calculate_hand_positions(
+    let mut hand_positions = calculate_hand_positions(
         card_line.card_list.len(),
         card_line.transform.translation.x,
         200.,
@@ -232,6 +256,15 @@ fn change_all_cards(
         card_line.transform.translation.z,
         card_line.transform.translation.y,
     );
+    // 横置的手牌更宽 按比例拉开间距
+    if let Some(state) = opt_state.as_ref() {
+        let size = Vec2::new(card3d_config.width, card3d_config.height);
+        let spread = state.orientation.footprint(size).x / size.x;
+        let center_x = card_line.transform.translation.x;
+        for position in hand_positions.iter_mut() {
+            position.translation.x = center_x + (position.translation.x - center_x) * spread;
+        }
+    }
     // 修改动画 和 Card内数据
     card_line
         .card_list
@@ -8,12 +8,14 @@ use bevy::pbr::{MeshMaterial3d, StandardMaterial};
 use bevy::prelude::*;
 use bevy_tween::tween::AnimationTarget;
 
+pub mod attach;
 mod card_mesh;
 pub mod card_namer;
 pub mod core;
 pub mod event;
 pub mod hand_card;
 pub mod move_card;
+pub mod select;
 pub mod card_state;
 pub mod card_material;
 
@@ -1,9 +1,13 @@
 use crate::card::card_state::CardState;
+use crate::card::select::Selected;
 use crate::prelude::event::DeclareDraggingDoneForCard;
 use crate::prelude::{Card, Dragged};
 use crate::tween::animation::play_card_going_back_to_place_animation;
 use bevy::app::App;
+use bevy::picking::backend::ray::{RayId, RayMap};
+use bevy::picking::pointer::{Location, PointerId};
 use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
 use bevy_tween::prelude::TweenEvent;
 use std::marker::PhantomData;
 
@@ -11,6 +15,19 @@ use std::marker::PhantomData;
 #[derive(Component, Copy, Clone)]
 pub struct Moveable;
 
+/// 开始拖拽时指针所在的相机
+#[derive(Component, Copy, Clone, Debug)]
+pub struct DragCamera(pub Entity);
+
+/// 跟随其他选中卡片一起拖拽
+#[derive(Component, Copy, Clone, Debug)]
+pub struct GroupDragOffset {
+    /// 正在被拖拽的卡片
+    pub leader: Entity,
+    /// 相对于 leader 的偏移
+    pub offset: Vec3,
+}
+
 pub struct MoveCardPlugin<P>
 where
     P: Component,
@@ -33,25 +50,50 @@ where
 /// 在3d的某个平面上一移动observer
 pub fn move_on_drag<P>() -> impl Fn(
     Trigger<Pointer<Drag>>,
-    Query<&mut Transform, (With<Card>, With<Moveable>)>,
-    Single<(&Camera, &GlobalTransform)>,
-    Single<&Window>,
+    Query<(&mut Transform, Option<&GroupDragOffset>), (With<Card>, With<Moveable>)>,
+    Query<&DragCamera>,
+    Query<(&Camera, &GlobalTransform)>,
+    Option<Single<Entity, With<PrimaryWindow>>>,
     Single<(&GlobalTransform, &Transform), (Without<Card>, With<P>)>,
 )
 where
     P: Component,
 {
-    move |drag, mut transforms, camera_query, windows, ground| {
+    move |drag, mut transforms, drag_cameras, cameras, primary_window, ground| {
         // 这个是需要修改的值
-        if let Ok(mut transform) = transforms.get_mut(drag.target()) {
-            let (camera, camera_transform) = *camera_query;
-
-            let Some(cursor_position) = windows.cursor_position() else {
-                return;
+        if let Ok((mut transform, _)) = transforms.get_mut(drag.target()) {
+            let primary_window = primary_window.map(|window| *window);
+            // 使用触发这次拖拽的指针位置 多个指针可以同时拖拽不同的卡片
+            // 优先使用开始拖拽时命中卡片的相机 其次是指针所在的渲染顺序最靠前的相机
+            let ray = if let Ok(drag_camera) = drag_cameras.get(drag.target()) {
+                cameras
+                    .get(drag_camera.0)
+                    .ok()
+                    .and_then(|(camera, camera_transform)| {
+                        camera_ray(
+                            &drag.pointer_location,
+                            camera,
+                            camera_transform,
+                            primary_window,
+                        )
+                    })
+            } else {
+                cameras
+                    .iter()
+                    .filter(|(camera, _)| camera.is_active)
+                    .filter_map(|(camera, camera_transform)| {
+                        camera_ray(
+                            &drag.pointer_location,
+                            camera,
+                            camera_transform,
+                            primary_window,
+                        )
+                        .map(|ray| (camera.order, ray))
+                    })
+                    .max_by_key(|(order, _)| *order)
+                    .map(|(_, ray)| ray)
             };
-
-            // Calculate a ray pointing from the camera into the world based on the cursor's position.
-            let Ok(ray) = camera.viewport_to_world(camera_transform, cursor_position) else {
+            let Some(ray) = ray else {
                 return;
             };
 
@@ -68,21 +110,81 @@ where
             transform.translation.x = point.x;
             transform.translation.y = point.y;
             transform.translation.z = ground_tr.translation.z;
+            let leader_translation = transform.translation;
+            // 选中的其他卡片保持相对位置跟随
+            for (mut transform, opt_group) in transforms.iter_mut() {
+                if let Some(group) = opt_group.filter(|group| group.leader == drag.target()) {
+                    transform.translation = leader_translation + group.offset;
+                    transform.translation.z = ground_tr.translation.z;
+                }
+            }
         }
     }
 }
 
+/// 根据指针位置计算相机的射线
+///
+/// 相机需要渲染到指针所在的窗口或纹理上，指针超出相机视口时仍然有效
+pub fn camera_ray(
+    location: &Location,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    primary_window: Option<Entity>,
+) -> Option<Ray3d> {
+    if camera.target.normalize(primary_window).as_ref() != Some(&location.target) {
+        return None;
+    }
+    let mut position = location.position;
+    if let Some(viewport) = &camera.viewport {
+        position -= camera.to_logical(viewport.physical_position)?;
+    }
+    camera.viewport_to_world(camera_transform, position).ok()
+}
+
+/// 指针所在的相机中渲染顺序最靠前的一个
+pub fn pointer_camera(
+    ray_map: &RayMap,
+    cameras: &Query<&Camera>,
+    pointer: PointerId,
+) -> Option<Entity> {
+    ray_map
+        .iter()
+        .filter(|(ray_id, _)| ray_id.pointer == pointer)
+        .filter_map(|(ray_id, _)| {
+            cameras
+                .get(ray_id.camera)
+                .ok()
+                .map(|camera| (camera.order, ray_id.camera))
+        })
+        .max_by_key(|(order, _)| *order)
+        .map(|(_, camera)| camera)
+}
+
+/// 取得指针在相机下的射线
+///
+/// 指定了相机时只使用该相机，否则使用 [`pointer_camera`]
+pub fn pointer_ray(
+    ray_map: &RayMap,
+    cameras: &Query<&Camera>,
+    pointer: PointerId,
+    opt_camera: Option<Entity>,
+) -> Option<Ray3d> {
+    let camera = opt_camera.or_else(|| pointer_camera(ray_map, cameras, pointer))?;
+    ray_map.map.get(&RayId::new(camera, pointer)).copied()
+}
+
 fn on_drag_start(
     drag_start: Trigger<Pointer<DragStart>>,
     // 可以被‘移动’的‘卡片’
-    mut card_transforms: Query<(&mut Transform, &Card), (With<Card>, With<Moveable>)>,
+    card_transforms: Query<(Entity, &Transform), (With<Card>, With<Moveable>)>,
+    query_selected: Query<(), With<Selected>>,
     mut commands: Commands,
     query: Query<&Children>,
 ) {
-    if let Ok((_card_transform, _card)) = card_transforms.get_mut(drag_start.target()) {
+    if let Ok((_, card_transform)) = card_transforms.get(drag_start.target()) {
         if let Ok(mut entity_commands) = commands.get_entity(drag_start.target()) {
             // info!("drag start");
-            entity_commands.insert(Dragged::Actively);
+            entity_commands.insert((Dragged::Actively, DragCamera(drag_start.hit.camera)));
         }
 
         if let Ok(children) = query.get(drag_start.target()) {
@@ -90,6 +192,27 @@ fn on_drag_start(
                 commands.entity(child).insert(Pickable::IGNORE);
             }
         }
+
+        // 拖拽选中的卡片时 其他选中的卡片一起移动
+        if query_selected.contains(drag_start.target()) {
+            for (entity, transform) in card_transforms.iter() {
+                if entity == drag_start.target() || !query_selected.contains(entity) {
+                    continue;
+                }
+                commands.entity(entity).insert((
+                    Dragged::Actively,
+                    GroupDragOffset {
+                        leader: drag_start.target(),
+                        offset: transform.translation - card_transform.translation,
+                    },
+                ));
+                if let Ok(children) = query.get(entity) {
+                    for child in children.iter() {
+                        commands.entity(child).insert(Pickable::IGNORE);
+                    }
+                }
+            }
+        }
     }
 }
 
@@ -103,6 +226,7 @@ fn back_to_origin_when_unused(
         &Name,
         Option<&CardState>,
     )>,
+    query_group: Query<(Entity, &GroupDragOffset)>,
     mut commands: Commands,
     query: Query<&Children>,
 ) {
@@ -118,6 +242,7 @@ fn back_to_origin_when_unused(
     )) = dragged_cards.get_mut(drag_end.target())
     {
         debug!("drag end!!!");
+        commands.entity(card_entity).remove::<DragCamera>();
         if let Ok(children) = query.get(drag_end.target()) {
             for child in children.iter() {
                 commands.entity(child).remove::<Pickable>();
@@ -133,6 +258,31 @@ fn back_to_origin_when_unused(
             opt_state.cloned(),
             &mut commands,
         );
+
+        for (entity, group) in query_group.iter() {
+            if group.leader != card_entity {
+                continue;
+            }
+            commands.entity(entity).remove::<GroupDragOffset>();
+            if let Ok(children) = query.get(entity) {
+                for child in children.iter() {
+                    commands.entity(child).remove::<Pickable>();
+                }
+            }
+            if let Ok((transform, _, card, mut dragged, name, opt_state)) =
+                dragged_cards.get_mut(entity)
+            {
+                *dragged = Dragged::GoingBackToPlace;
+                play_card_going_back_to_place_animation(
+                    entity,
+                    card,
+                    &transform,
+                    name,
+                    opt_state.cloned(),
+                    &mut commands,
+                );
+            }
+        }
     }
 }
 
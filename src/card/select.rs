@@ -0,0 +1,312 @@
+use crate::prelude::{Card, CardLine, Dragged, HandCard, HandCardPlane, pointer_camera};
+use crate::targeting::TargetingArrow;
+use crate::zone::Zone;
+use bevy::asset::RenderAssetUsages;
+use bevy::picking::backend::ray::RayMap;
+use bevy::picking::hover::HoverMap;
+use bevy::picking::pointer::{PointerId, PointerLocation};
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::view::NoFrustumCulling;
+
+/// 被选中的卡片
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct Selected;
+
+/// 选中的卡片发生变化
+#[derive(Event, Clone, Debug)]
+pub struct SelectionChanged {
+    pub selected: Vec<Entity>,
+}
+
+/// 选中的样式
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct SelectionConfig {
+    /// 选中卡片的边框颜色
+    pub color: Color,
+    /// 框选区域的颜色
+    pub marquee_color: Color,
+}
+
+impl Default for SelectionConfig {
+    fn default() -> Self {
+        Self {
+            color: Color::srgb(0.3, 0.7, 1.0),
+            marquee_color: Color::srgba(0.3, 0.7, 1.0, 0.2),
+        }
+    }
+}
+
+/// shift 多选的起点
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct SelectionAnchor(pub Option<Entity>);
+
+/// 框选
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Marquee {
+    pub camera: Entity,
+    /// 视口坐标
+    pub start: Vec2,
+    pub end: Vec2,
+}
+
+pub struct SelectPlugin;
+
+impl Plugin for SelectPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SelectionConfig>()
+            .init_resource::<SelectionAnchor>()
+            .add_event::<SelectionChanged>()
+            .add_observer(click_to_select)
+            .add_systems(
+                Update,
+                (
+                    start_marquee,
+                    update_marquee,
+                    finish_marquee,
+                    selection_changed_event,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// ctrl 点击切换选中 shift 点击选中手牌中的一段 直接点击清空选中
+#[allow(clippy::too_many_arguments)]
+fn click_to_select(
+    click: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut anchor: ResMut<SelectionAnchor>,
+    query_card: Query<(Has<Selected>, Option<&HandCard>), With<Card>>,
+    query_dragged: Query<(), With<Dragged>>,
+    query_card_line: Query<&CardLine>,
+    query_selected: Query<Entity, With<Selected>>,
+) {
+    if click.button != PointerButton::Primary {
+        return;
+    }
+    let card_entity = click.target();
+    let Ok((selected, opt_hand_card)) = query_card.get(card_entity) else {
+        return;
+    };
+    let ctrl = keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if shift {
+        let range = anchor.0.zip(opt_hand_card).and_then(|(anchor, hand_card)| {
+            let card_line = query_card_line.get(hand_card.belong_to_card_line?).ok()?;
+            let from = card_line.card_list.iter().position(|x| *x == anchor)?;
+            let to = card_line.card_list.iter().position(|x| *x == card_entity)?;
+            Some(card_line.card_list[from.min(to)..=from.max(to)].to_vec())
+        });
+        for entity in range.unwrap_or(vec![card_entity]) {
+            commands.entity(entity).insert(Selected);
+        }
+        // 没有起点时 这张卡片作为之后 shift 点击的起点
+        if anchor.0.is_none() {
+            anchor.0 = Some(card_entity);
+        }
+    } else if ctrl {
+        if selected {
+            commands.entity(card_entity).remove::<Selected>();
+        } else {
+            commands.entity(card_entity).insert(Selected);
+        }
+        anchor.0 = Some(card_entity);
+    } else if !query_dragged.contains(card_entity) {
+        // 拖拽结束时也会触发点击 拖拽一组选中的卡片后保留选中
+        for entity in query_selected.iter() {
+            commands.entity(entity).remove::<Selected>();
+        }
+        anchor.0 = Some(card_entity);
+    }
+}
+
+fn selection_changed_event(
+    mut selection_changed: EventWriter<SelectionChanged>,
+    added: Query<(), Added<Selected>>,
+    mut removed: RemovedComponents<Selected>,
+    query_selected: Query<Entity, With<Selected>>,
+) {
+    let removed_any = removed.read().count() > 0;
+    if removed_any || !added.is_empty() {
+        selection_changed.write(SelectionChanged {
+            selected: query_selected.iter().collect(),
+        });
+    }
+}
+
+/// 指针在相机视口中的位置
+fn pointer_in_viewport(location: &PointerLocation, camera: &Camera) -> Option<Vec2> {
+    let location = location.location()?;
+    let mut position = location.position;
+    if let Some(viewport) = &camera.viewport {
+        position -= camera.to_logical(viewport.physical_position)?;
+    }
+    Some(position)
+}
+
+/// 框选的最小边长(像素) 更小时当作单击 不改变选中
+const MARQUEE_MIN_SIZE: f32 = 4.0;
+
+/// 在桌面或背景上按下时开始框选 按在卡片、场地和 UI 上或者正在选择目标时不会开始
+#[allow(clippy::too_many_arguments)]
+fn start_marquee(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    config: Res<SelectionConfig>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    hover_map: Res<HoverMap>,
+    ray_map: Res<RayMap>,
+    query_pointer: Query<(&PointerId, &PointerLocation)>,
+    query_camera: Query<&Camera>,
+    query_card: Query<(), With<Card>>,
+    query_zone: Query<(), With<Zone>>,
+    query_node: Query<(), With<Node>>,
+    query_parent: Query<&ChildOf>,
+    query_targeting: Query<(), With<TargetingArrow>>,
+) {
+    if !mouse.just_pressed(MouseButton::Left) || !query_targeting.is_empty() {
+        return;
+    }
+    let on_interactive = hover_map.get(&PointerId::Mouse).is_some_and(|hovered| {
+        hovered.keys().any(|entity| {
+            query_zone.contains(*entity)
+                || query_node.contains(*entity)
+                || query_card.contains(*entity)
+                || query_parent
+                    .get(*entity)
+                    .is_ok_and(|parent| query_card.contains(parent.parent()))
+        })
+    });
+    if on_interactive {
+        return;
+    }
+    let Some(camera_entity) = pointer_camera(&ray_map, &query_camera, PointerId::Mouse) else {
+        return;
+    };
+    let Some((_, location)) = query_pointer
+        .iter()
+        .find(|(id, _)| **id == PointerId::Mouse)
+    else {
+        return;
+    };
+    let Some(start) = query_camera
+        .get(camera_entity)
+        .ok()
+        .and_then(|camera| pointer_in_viewport(location, camera))
+    else {
+        return;
+    };
+    commands.spawn((
+        Name::new("marquee"),
+        Marquee {
+            camera: camera_entity,
+            start,
+            end: start,
+        },
+        Mesh3d(meshes.add(marquee_mesh([Vec3::ZERO; 4]))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: config.marquee_color,
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            cull_mode: None,
+            ..default()
+        })),
+        Transform::default(),
+        NoFrustumCulling,
+        Pickable::IGNORE,
+    ));
+}
+
+fn update_marquee(
+    mut query_marquee: Query<(&mut Marquee, &Mesh3d)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    query_pointer: Query<(&PointerId, &PointerLocation)>,
+    query_camera: Query<(&Camera, &GlobalTransform)>,
+    plane: Single<&GlobalTransform, With<HandCardPlane>>,
+) {
+    let Some((_, location)) = query_pointer
+        .iter()
+        .find(|(id, _)| **id == PointerId::Mouse)
+    else {
+        return;
+    };
+    for (mut marquee, mesh) in query_marquee.iter_mut() {
+        let Ok((camera, camera_transform)) = query_camera.get(marquee.camera) else {
+            continue;
+        };
+        if let Some(end) = pointer_in_viewport(location, camera) {
+            marquee.end = end;
+        }
+        let (min, max) = (
+            marquee.start.min(marquee.end),
+            marquee.start.max(marquee.end),
+        );
+        // 把四个角投影到手牌平面上
+        let corners = [min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)].map(|corner| {
+            camera
+                .viewport_to_world(camera_transform, corner)
+                .ok()
+                .and_then(|ray| {
+                    ray.intersect_plane(plane.translation(), InfinitePlane3d::new(plane.up()))
+                        .map(|distance| ray.get_point(distance))
+                })
+                .unwrap_or(Vec3::ZERO)
+        });
+        if let Some(mesh) = meshes.get_mut(&mesh.0) {
+            *mesh = marquee_mesh(corners);
+        }
+    }
+}
+
+/// 松开时选中框内的卡片 按住 ctrl 或 shift 时追加选中
+fn finish_marquee(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    query_marquee: Query<(Entity, &Marquee)>,
+    query_camera: Query<(&Camera, &GlobalTransform)>,
+    query_card: Query<(Entity, &GlobalTransform, Has<Selected>), With<Card>>,
+) {
+    if !mouse.just_released(MouseButton::Left) {
+        return;
+    }
+    let append = keyboard.any_pressed([
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+        KeyCode::ShiftLeft,
+        KeyCode::ShiftRight,
+    ]);
+    for (marquee_entity, marquee) in query_marquee.iter() {
+        commands.entity(marquee_entity).despawn();
+        let Ok((camera, camera_transform)) = query_camera.get(marquee.camera) else {
+            continue;
+        };
+        let rect = Rect::from_corners(marquee.start, marquee.end);
+        if rect.width() < MARQUEE_MIN_SIZE || rect.height() < MARQUEE_MIN_SIZE {
+            continue;
+        }
+        for (card_entity, card_transform, selected) in query_card.iter() {
+            let inside = camera
+                .world_to_viewport(camera_transform, card_transform.translation())
+                .is_ok_and(|position| rect.contains(position));
+            if inside && !selected {
+                commands.entity(card_entity).insert(Selected);
+            } else if !inside && selected && !append {
+                commands.entity(card_entity).remove::<Selected>();
+            }
+        }
+    }
+}
+
+fn marquee_mesh(corners: [Vec3; 4]) -> Mesh {
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, corners.to_vec())
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, vec![Vec3::Z; 4])
+    .with_inserted_indices(Indices::U32(vec![0, 1, 2, 0, 2, 3]))
+}
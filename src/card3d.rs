@@ -1,10 +1,16 @@
+use crate::board::BoardPlugin;
 use crate::card::card_material::CardMaterialPlugin;
 use crate::card::card_state::{CardState, CardStatePlugin};
+use crate::choice::CardChoicePlugin;
 use crate::highlight::HighlightPlugin;
+use crate::mulligan::MulliganPlugin;
+use crate::prelude::attach::AttachPlugin;
 use crate::prelude::card_namer::CardNamerPlugin;
+use crate::prelude::select::SelectPlugin;
 use crate::prelude::{Card, HandCardPlane, HandCardPlugin};
 #[cfg(feature = "image_preview")]
 use crate::preview_plugins::PreviewPlugins;
+use crate::targeting::TargetingPlugin;
 use crate::tween::ExtTweenPlugins;
 use crate::zone::ZonePlugin;
 use crate::zone::desk_zone::DeskZone;
@@ -50,6 +56,11 @@ impl Plugin for Card3DPlugins {
             ZonePlugin,
             HighlightPlugin,
             CardStatePlugin,
+            TargetingPlugin,
+            SelectPlugin,
+            AttachPlugin,
+            BoardPlugin,
+            (CardChoicePlugin, MulliganPlugin),
         ))
         .register_type::<CardState>()
         .register_type::<DeskZone>()
@@ -0,0 +1,389 @@
+use crate::card::card_state::CardState;
+use crate::card::hand_card::HandCard;
+use crate::prelude::{Card, Card3DConfig, Moveable};
+use crate::tween::animation::{
+    play_card_going_back_to_place_animation, play_card_going_back_to_trans_animation,
+};
+use crate::zone::deck_zone::DeckCard;
+use crate::zone::desk_zone::DeskCard;
+use bevy::prelude::*;
+
+/// 从几张卡片中选择 例如发现、调度、占卜
+///
+/// 添加到任意实体上开始选择，卡片会排成弧形展示在相机前，
+/// 点击卡片切换选中，确认后卡片回到原来的位置并触发 [`ChoiceMade`]，实体会被删除。
+/// 直接删除实体时卡片回到原来的位置，不触发 [`ChoiceMade`]
+#[derive(Component, Clone, Debug)]
+pub struct CardChoice {
+    pub cards: Vec<Entity>,
+    /// 至少选择的数量
+    pub min: usize,
+    /// 最多选择的数量
+    pub max: usize,
+}
+
+impl CardChoice {
+    /// 选择其中一张
+    pub fn one_of(cards: Vec<Entity>) -> Self {
+        Self {
+            cards,
+            min: 1,
+            max: 1,
+        }
+    }
+
+    /// 选择 min 到 max 张
+    pub fn between(cards: Vec<Entity>, min: usize, max: usize) -> Self {
+        Self { cards, min, max }
+    }
+}
+
+/// 正在被选择的卡片
+#[derive(Component, Clone, Copy, Debug)]
+pub struct ChoiceCard {
+    pub choice: Entity,
+    /// 在弧形中的位置
+    pub slot: Transform,
+    moveable: bool,
+    /// 选择开始时卡片所在的手牌、场地或牌堆
+    owner: Option<Entity>,
+}
+
+/// 被选中的卡片
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct Chosen;
+
+/// 确认选择 选中的数量不符合要求时忽略
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ConfirmCardChoice {
+    pub choice: Entity,
+}
+
+/// 完成了选择 卡片按照 [`CardChoice`] 中的顺序排列
+#[derive(Event, Clone, Debug)]
+pub struct ChoiceMade {
+    pub choice: Entity,
+    pub chosen: Vec<Entity>,
+    pub rejected: Vec<Entity>,
+}
+
+/// 确认按钮
+#[derive(Component, Clone, Copy, Debug)]
+pub struct CardChoiceButton {
+    pub choice: Entity,
+}
+
+/// 选择的样式
+#[derive(Resource, Clone, Debug)]
+pub struct CardChoiceConfig {
+    /// 弧形离相机的距离
+    pub distance: f32,
+    /// 卡片之间的间隔
+    pub spacing: f32,
+    /// 弧形的弯曲程度 两边的卡片向下和向外倾斜
+    pub curvature: f32,
+    /// 选中的卡片向上移动的距离
+    pub chosen_lift: f32,
+    /// 选中卡片的边框颜色
+    pub color: Color,
+    /// 显示确认按钮 关闭后需要自己触发 [`ConfirmCardChoice`]
+    pub show_button: bool,
+    pub button_text: String,
+    /// 按钮文字的字体 默认使用 bevy 自带的字体
+    pub button_font: Handle<Font>,
+}
+
+impl Default for CardChoiceConfig {
+    fn default() -> Self {
+        Self {
+            distance: 14.0,
+            spacing: 0.4,
+            curvature: 0.08,
+            chosen_lift: 0.6,
+            color: Color::srgb(1.0, 0.8, 0.2),
+            show_button: true,
+            button_text: "OK".to_string(),
+            button_font: Handle::default(),
+        }
+    }
+}
+
+pub struct CardChoicePlugin;
+
+impl Plugin for CardChoicePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CardChoiceConfig>()
+            .add_observer(click_to_choose)
+            .add_observer(confirm_card_choice)
+            .add_observer(release_choice_cards)
+            .add_systems(Update, (added_card_choice, update_choice_button).chain());
+    }
+}
+
+/// 卡片排成弧形 正面朝向相机
+fn added_card_choice(
+    mut commands: Commands,
+    query_choice: Query<(Entity, &CardChoice), Added<CardChoice>>,
+    query_card: Query<(&Transform, &Name, Has<Moveable>), With<Card>>,
+    query_owner: Query<CardOwner>,
+    query_camera: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+    config: Res<CardChoiceConfig>,
+    card3d_config: Res<Card3DConfig>,
+) {
+    let Some((_, camera_transform)) = query_camera
+        .iter()
+        .filter(|(camera, _)| camera.is_active)
+        .max_by_key(|(camera, _)| camera.order)
+    else {
+        return;
+    };
+    let (_, rotation, translation) = camera_transform.to_scale_rotation_translation();
+    let center =
+        Transform::from_translation(translation + rotation * Vec3::NEG_Z * config.distance)
+            .with_rotation(rotation);
+    for (choice_entity, choice) in query_choice.iter() {
+        let count = choice.cards.len() as f32;
+        for (index, card_entity) in choice.cards.iter().enumerate() {
+            let Ok((transform, name, moveable)) = query_card.get(*card_entity) else {
+                continue;
+            };
+            let offset = index as f32 - (count - 1.0) / 2.0;
+            let x = offset * (card3d_config.width + config.spacing);
+            let local = Transform::from_xyz(x, -config.curvature * x * x, 0.0)
+                .with_rotation(Quat::from_rotation_z(-offset * config.curvature));
+            let slot = center * local;
+            commands
+                .entity(*card_entity)
+                .remove::<Moveable>()
+                .insert(ChoiceCard {
+                    choice: choice_entity,
+                    slot,
+                    moveable,
+                    owner: card_owner(query_owner.get(*card_entity).ok()),
+                });
+            play_card_going_back_to_trans_animation(
+                *card_entity,
+                slot,
+                transform,
+                name,
+                &mut commands,
+            );
+        }
+        if config.show_button {
+            commands
+                .spawn((
+                    Name::new(format!("card choice button of {:?}", choice_entity)),
+                    Node {
+                        position_type: PositionType::Absolute,
+                        width: Val::Percent(100.0),
+                        bottom: Val::Percent(10.0),
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    Pickable::IGNORE,
+                ))
+                .with_children(|parent| {
+                    parent
+                        .spawn((
+                            CardChoiceButton {
+                                choice: choice_entity,
+                            },
+                            Button,
+                            Node {
+                                padding: UiRect::axes(Val::Px(24.0), Val::Px(8.0)),
+                                ..default()
+                            },
+                            BackgroundColor(Color::srgba(0.2, 0.2, 0.2, 0.8)),
+                        ))
+                        .with_child((
+                            Text::new(config.button_text.clone()),
+                            TextFont {
+                                font: config.button_font.clone(),
+                                font_size: 24.0,
+                                ..default()
+                            },
+                            Pickable::IGNORE,
+                        ));
+                });
+        }
+    }
+}
+
+/// 点击切换选中 只能选一张时点击其他卡片会换成那一张
+fn click_to_choose(
+    click: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    query_card: Query<(&ChoiceCard, &Transform, &Name, Has<Chosen>)>,
+    query_choice: Query<&CardChoice>,
+    query_chosen: Query<(Entity, &ChoiceCard, &Transform, &Name), With<Chosen>>,
+    config: Res<CardChoiceConfig>,
+) {
+    if click.button != PointerButton::Primary {
+        return;
+    }
+    let card_entity = click.target();
+    let Ok((choice_card, transform, name, chosen)) = query_card.get(card_entity) else {
+        return;
+    };
+    let Ok(choice) = query_choice.get(choice_card.choice) else {
+        return;
+    };
+    if chosen {
+        commands.entity(card_entity).remove::<Chosen>();
+        play_card_going_back_to_trans_animation(
+            card_entity,
+            choice_card.slot,
+            transform,
+            name,
+            &mut commands,
+        );
+        return;
+    }
+    let others: Vec<_> = query_chosen
+        .iter()
+        .filter(|(_, other, _, _)| other.choice == choice_card.choice)
+        .collect();
+    if others.len() >= choice.max {
+        if choice.max != 1 {
+            return;
+        }
+        for (other_entity, other, other_transform, other_name) in others {
+            commands.entity(other_entity).remove::<Chosen>();
+            play_card_going_back_to_trans_animation(
+                other_entity,
+                other.slot,
+                other_transform,
+                other_name,
+                &mut commands,
+            );
+        }
+    }
+    commands.entity(card_entity).insert(Chosen);
+    let mut lifted = choice_card.slot;
+    lifted.translation += lifted.rotation * Vec3::Y * config.chosen_lift;
+    play_card_going_back_to_trans_animation(card_entity, lifted, transform, name, &mut commands);
+}
+
+/// 按钮在选中数量符合要求时可以点击 也可以按回车确认
+fn update_choice_button(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut query_button: Query<(&CardChoiceButton, &Interaction, &mut BackgroundColor)>,
+    query_choice: Query<(Entity, &CardChoice)>,
+    query_chosen: Query<&ChoiceCard, With<Chosen>>,
+) {
+    let ready = |choice_entity: Entity| {
+        query_choice.get(choice_entity).is_ok_and(|(_, choice)| {
+            let count = query_chosen
+                .iter()
+                .filter(|card| card.choice == choice_entity)
+                .count();
+            count >= choice.min && count <= choice.max
+        })
+    };
+    for (button, interaction, mut background) in query_button.iter_mut() {
+        let ready = ready(button.choice);
+        let color = match (ready, interaction) {
+            (false, _) => Color::srgba(0.2, 0.2, 0.2, 0.8),
+            (true, Interaction::None) => Color::srgba(0.2, 0.5, 0.3, 0.9),
+            (true, _) => Color::srgba(0.3, 0.7, 0.4, 0.9),
+        };
+        if background.0 != color {
+            background.0 = color;
+        }
+    }
+    let pressed: Vec<Entity> = query_button
+        .iter()
+        .filter(|(_, interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(button, _, _)| button.choice)
+        .collect();
+    let enter = keyboard.just_pressed(KeyCode::Enter);
+    for (choice_entity, _) in query_choice.iter() {
+        if (enter || pressed.contains(&choice_entity)) && ready(choice_entity) {
+            commands.trigger(ConfirmCardChoice {
+                choice: choice_entity,
+            });
+        }
+    }
+}
+
+fn confirm_card_choice(
+    trigger: Trigger<ConfirmCardChoice>,
+    mut commands: Commands,
+    query_choice: Query<&CardChoice>,
+    query_chosen: Query<&ChoiceCard, With<Chosen>>,
+) {
+    let choice_entity = trigger.choice;
+    let Ok(choice) = query_choice.get(choice_entity) else {
+        return;
+    };
+    let (chosen, rejected): (Vec<Entity>, Vec<Entity>) =
+        choice.cards.iter().partition(|card_entity| {
+            query_chosen
+                .get(**card_entity)
+                .is_ok_and(|card| card.choice == choice_entity)
+        });
+    if chosen.len() < choice.min || chosen.len() > choice.max {
+        return;
+    }
+    commands.trigger(ChoiceMade {
+        choice: choice_entity,
+        chosen,
+        rejected,
+    });
+    commands.entity(choice_entity).despawn();
+}
+
+/// 选择结束 卡片回到原来的位置
+fn release_choice_cards(
+    trigger: Trigger<OnRemove, CardChoice>,
+    mut commands: Commands,
+    query_card: Query<(Entity, &ChoiceCard, &Card, &Transform, &Name)>,
+    query_owner: Query<CardOwner>,
+    query_state: Query<&CardState>,
+    query_button: Query<(&CardChoiceButton, &ChildOf)>,
+) {
+    for (card_entity, choice_card, card, transform, name) in query_card.iter() {
+        if choice_card.choice != trigger.target() {
+            continue;
+        }
+        let mut entity_commands = commands.entity(card_entity);
+        entity_commands.remove::<(ChoiceCard, Chosen)>();
+        // 选择结果已经把卡片送去别处 例如调度放回牌堆 不再回到原来的位置
+        if card_owner(query_owner.get(card_entity).ok()) != choice_card.owner {
+            continue;
+        }
+        if choice_card.moveable {
+            entity_commands.insert(Moveable);
+        }
+        play_card_going_back_to_place_animation(
+            card_entity,
+            card,
+            transform,
+            name,
+            query_state.get(card_entity).ok().cloned(),
+            &mut commands,
+        );
+    }
+    for (button, parent) in query_button.iter() {
+        if button.choice == trigger.target() {
+            commands.entity(parent.parent()).despawn();
+        }
+    }
+}
+
+type CardOwner = (
+    Option<&'static HandCard>,
+    Option<&'static DeskCard>,
+    Option<&'static DeckCard>,
+);
+
+/// 卡片所在的手牌、场地或牌堆
+fn card_owner(
+    owner: Option<(Option<&HandCard>, Option<&DeskCard>, Option<&DeckCard>)>,
+) -> Option<Entity> {
+    let (hand, desk, deck) = owner?;
+    hand.and_then(|hand| hand.belong_to_card_line)
+        .or(desk.and_then(|desk| desk.belongs_to_desk))
+        .or(deck.and_then(|deck| deck.belongs_to_deck))
+}
@@ -1,5 +1,9 @@
+use crate::choice::{CardChoiceConfig, Chosen};
+use crate::prelude::select::{Selected, SelectionConfig};
+use crate::targeting::{TargetingArrowConfig, ValidTarget};
 use bevy::prelude::*;
 use bevy_mod_outline::{OutlinePlugin, OutlineStencil, OutlineVolume};
+use bevy_tween::TweenSystemSet;
 use bevy_tween::combinator::{parallel, tween};
 use bevy_tween::component_tween_system;
 use bevy_tween::prelude::*;
@@ -14,6 +18,27 @@ pub struct HighlightPlugin;
 #[derive(Component, Clone, Debug)]
 pub struct HighlightTween(pub Entity);
 
+/// 边框正在显示选择、目标或框选的颜色 而不是 [`Highlight`]
+#[derive(Component, Clone, Copy, Debug)]
+struct OutlineOverride;
+
+type OutlineFlags = (
+    Entity,
+    Option<&'static mut OutlineVolume>,
+    Has<Chosen>,
+    Has<ValidTarget>,
+    Has<Selected>,
+    Option<&'static Highlight>,
+    Has<OutlineOverride>,
+);
+
+type OutlineFilter = Or<(
+    With<Chosen>,
+    With<ValidTarget>,
+    With<Selected>,
+    With<OutlineOverride>,
+)>;
+
 impl Plugin for HighlightPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(OutlinePlugin);
@@ -25,6 +50,10 @@ impl Plugin for HighlightPlugin {
                 remove_highlights,
             ),
         );
+        app.add_systems(
+            PostUpdate,
+            resolve_outline.after(TweenSystemSet::ApplyTween),
+        );
         app.add_tween_systems((
             component_tween_system::<HighlightWidth>(),
             component_tween_system::<HighlightColor>(),
@@ -99,6 +128,65 @@ fn remove_highlights(
     }
 }
 
+/// 卡片同时有多种边框时只显示优先级最高的一种
+///
+/// 选择中选中 > 可以指向的目标 > 框选选中 > [`Highlight`]
+///
+/// 在补间动画之后运行 覆盖 [`Highlight`] 的颜色动画
+fn resolve_outline(
+    mut commands: Commands,
+    mut query: Query<OutlineFlags, OutlineFilter>,
+    choice_config: Res<CardChoiceConfig>,
+    targeting_config: Res<TargetingArrowConfig>,
+    selection_config: Res<SelectionConfig>,
+) {
+    for (entity, opt_outline, chosen, valid_target, selected, opt_highlight, overridden) in
+        query.iter_mut()
+    {
+        let opt_colour = if chosen {
+            Some(choice_config.color)
+        } else if valid_target {
+            Some(targeting_config.target_color)
+        } else if selected {
+            Some(selection_config.color)
+        } else {
+            None
+        };
+        match (opt_colour, opt_outline) {
+            (Some(colour), Some(mut outline)) => {
+                if !outline.visible || outline.colour != colour {
+                    outline.visible = true;
+                    outline.colour = colour;
+                }
+                if !overridden {
+                    commands.entity(entity).insert(OutlineOverride);
+                }
+            }
+            (Some(colour), None) => {
+                commands.entity(entity).insert((
+                    OutlineVolume {
+                        visible: true,
+                        colour,
+                        width: 10.0,
+                    },
+                    OutlineStencil::default(),
+                    OutlineOverride,
+                ));
+            }
+            (None, opt_outline) => {
+                // 保留用户自己的高亮
+                if let Some(mut outline) = opt_outline {
+                    match opt_highlight {
+                        Some(highlight) => outline.colour = highlight.color,
+                        None => outline.visible = false,
+                    }
+                }
+                commands.entity(entity).remove::<OutlineOverride>();
+            }
+        }
+    }
+}
+
 /// 高亮的宽度
 #[derive(Debug, Default, Clone, PartialEq, Reflect)]
 pub struct HighlightWidth {
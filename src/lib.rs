@@ -1,8 +1,12 @@
+pub mod board;
 mod card;
 mod card3d;
+pub mod choice;
 pub mod tween;
 pub mod zone;
 pub mod highlight;
+pub mod mulligan;
+pub mod targeting;
 #[cfg(feature = "image_preview")]
 pub mod preview_plugins;
 
@@ -0,0 +1,291 @@
+use crate::choice::{CardChoice, ChoiceMade};
+use crate::prelude::{CardLine, Moveable};
+use crate::zone::deck_zone::{DeckZone, DrawCard, ShuffleDeck, relayout_deck};
+use crate::zone::{Zone, send_card_to};
+use bevy::prelude::*;
+
+/// 起手调度
+///
+/// 发完起手牌后添加到任意实体上开始调度，结束后触发 [`MulliganFinished`]，实体会被删除
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Mulligan {
+    /// 牌堆 DeckZone
+    pub deck: Entity,
+    /// 手牌 CardLine
+    pub hand: Entity,
+    pub rule: MulliganRule,
+}
+
+/// 调度的规则
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MulliganRule {
+    /// 选中的卡片放回牌堆洗牌 再抽同样数量的卡片
+    #[default]
+    Free,
+    /// 伦敦规则 选中任意卡片时整手放回牌堆洗牌重抽
+    ///
+    /// 不选卡片确认时保留手牌，再选择调度次数张卡片放到牌堆底
+    London,
+    /// 选中的卡片放回牌堆洗牌 抽的卡片比放回的少一张
+    Partial,
+}
+
+/// 调度结束
+#[derive(Event, Clone, Debug)]
+pub struct MulliganFinished {
+    pub mulligan: Entity,
+    /// 最后的手牌
+    pub hand: Vec<Entity>,
+    /// 伦敦规则下重抽的次数 其他规则为 0 或 1
+    pub mulligans: usize,
+}
+
+/// 调度的进度
+#[derive(Component, Clone, Debug)]
+pub struct MulliganStep {
+    pub phase: MulliganPhase,
+    /// 起手牌的数量
+    pub opening: usize,
+    pub mulligans: usize,
+    timer: Timer,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MulliganPhase {
+    /// 等待起手牌进入手牌
+    Starting,
+    /// 等待玩家选择 bottom 为选择放到牌堆底的卡片
+    Choosing {
+        choice: Entity,
+        bottom: bool,
+    },
+    /// 卡片回到牌堆
+    Returning {
+        draw: usize,
+        to_bottom: Vec<Entity>,
+    },
+    /// 洗牌后逐张抽卡
+    Dealing {
+        draw: usize,
+    },
+    Finished,
+}
+
+impl MulliganStep {
+    fn next(&mut self, phase: MulliganPhase, seconds: f32) {
+        self.phase = phase;
+        self.timer = Timer::from_seconds(seconds, TimerMode::Once);
+    }
+}
+
+pub struct MulliganPlugin;
+
+impl Plugin for MulliganPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(mulligan_choice_made)
+            .add_systems(Update, (added_mulligan, advance_mulligan).chain());
+    }
+}
+
+fn added_mulligan(mut commands: Commands, query: Query<Entity, Added<Mulligan>>) {
+    for entity in query.iter() {
+        commands.entity(entity).insert(MulliganStep {
+            phase: MulliganPhase::Starting,
+            opening: 0,
+            mulligans: 0,
+            timer: Timer::from_seconds(0.3, TimerMode::Once),
+        });
+    }
+}
+
+fn advance_mulligan(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &Mulligan, &mut MulliganStep)>,
+    query_hand: Query<&CardLine>,
+) {
+    for (entity, mulligan, mut step) in query.iter_mut() {
+        if !step.timer.tick(time.delta()).finished() {
+            continue;
+        }
+        let hand = query_hand
+            .get(mulligan.hand)
+            .map(|card_line| card_line.card_list.clone())
+            .unwrap_or_default();
+        match step.phase.clone() {
+            MulliganPhase::Starting => {
+                if step.mulligans == 0 {
+                    step.opening = hand.len();
+                }
+                start_round(entity, mulligan, &mut step, hand, &mut commands);
+            }
+            MulliganPhase::Choosing { .. } => {}
+            MulliganPhase::Returning { draw, to_bottom } => {
+                if to_bottom.is_empty() {
+                    commands.trigger(ShuffleDeck {
+                        deck: mulligan.deck,
+                    });
+                    step.next(MulliganPhase::Dealing { draw }, 0.6);
+                } else {
+                    put_on_bottom(&mut commands, mulligan.deck, to_bottom);
+                    step.next(MulliganPhase::Finished, 0.3);
+                }
+            }
+            MulliganPhase::Dealing { draw } => {
+                if draw > 0 {
+                    commands.trigger(DrawCard {
+                        deck: mulligan.deck,
+                        to: mulligan.hand,
+                    });
+                    step.next(MulliganPhase::Dealing { draw: draw - 1 }, 0.15);
+                } else if mulligan.rule == MulliganRule::London {
+                    // 重抽后再决定是否保留
+                    step.next(MulliganPhase::Starting, 0.3);
+                } else {
+                    step.next(MulliganPhase::Finished, 0.3);
+                }
+            }
+            MulliganPhase::Finished => {
+                commands.trigger(MulliganFinished {
+                    mulligan: entity,
+                    hand,
+                    mulligans: step.mulligans,
+                });
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+/// 展示手牌让玩家选择要换掉的卡片
+fn start_round(
+    entity: Entity,
+    mulligan: &Mulligan,
+    step: &mut MulliganStep,
+    hand: Vec<Entity>,
+    commands: &mut Commands,
+) {
+    // 伦敦规则下手牌不能全部放到牌堆底
+    let max = if mulligan.rule == MulliganRule::London && step.mulligans + 1 >= step.opening {
+        0
+    } else {
+        hand.len()
+    };
+    let choice = commands
+        .spawn((
+            Name::new(format!("mulligan choice of {:?}", entity)),
+            CardChoice::between(hand, 0, max),
+        ))
+        .id();
+    step.phase = MulliganPhase::Choosing {
+        choice,
+        bottom: false,
+    };
+}
+
+fn mulligan_choice_made(
+    trigger: Trigger<ChoiceMade>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &Mulligan, &mut MulliganStep)>,
+    query_hand: Query<&CardLine>,
+) {
+    let Some((entity, mulligan, mut step)) = query.iter_mut().find(|(_, _, step)| {
+        matches!(step.phase, MulliganPhase::Choosing { choice, .. } if choice == trigger.choice)
+    }) else {
+        return;
+    };
+    let MulliganPhase::Choosing { bottom, .. } = step.phase else {
+        return;
+    };
+    let chosen = trigger.chosen.clone();
+    match mulligan.rule {
+        MulliganRule::London if bottom => {
+            return_to_deck(&mut commands, mulligan.deck, &chosen);
+            step.next(
+                MulliganPhase::Returning {
+                    draw: 0,
+                    to_bottom: chosen,
+                },
+                0.5,
+            );
+        }
+        MulliganRule::London if chosen.is_empty() => {
+            if step.mulligans == 0 {
+                step.next(MulliganPhase::Finished, 0.0);
+                return;
+            }
+            // 保留手牌 选择放到牌堆底的卡片
+            let hand = query_hand
+                .get(mulligan.hand)
+                .map(|card_line| card_line.card_list.clone())
+                .unwrap_or_default();
+            let count = step.mulligans.min(hand.len());
+            let choice = commands
+                .spawn((
+                    Name::new(format!("mulligan bottom choice of {:?}", entity)),
+                    CardChoice::between(hand, count, count),
+                ))
+                .id();
+            step.phase = MulliganPhase::Choosing {
+                choice,
+                bottom: true,
+            };
+        }
+        MulliganRule::London => {
+            // 整手放回
+            let hand = [chosen, trigger.rejected.clone()].concat();
+            return_to_deck(&mut commands, mulligan.deck, &hand);
+            step.mulligans += 1;
+            let draw = step.opening;
+            step.next(
+                MulliganPhase::Returning {
+                    draw,
+                    to_bottom: vec![],
+                },
+                0.5,
+            );
+        }
+        MulliganRule::Free | MulliganRule::Partial if chosen.is_empty() => {
+            step.next(MulliganPhase::Finished, 0.0);
+        }
+        MulliganRule::Free | MulliganRule::Partial => {
+            return_to_deck(&mut commands, mulligan.deck, &chosen);
+            let draw = if mulligan.rule == MulliganRule::Partial {
+                chosen.len() - 1
+            } else {
+                chosen.len()
+            };
+            step.mulligans = 1;
+            step.next(
+                MulliganPhase::Returning {
+                    draw,
+                    to_bottom: vec![],
+                },
+                0.5,
+            );
+        }
+    }
+}
+
+fn return_to_deck(commands: &mut Commands, deck: Entity, cards: &[Entity]) {
+    for card in cards {
+        send_card_to(commands, *card, deck);
+        commands.entity(*card).remove::<Moveable>();
+    }
+}
+
+/// 回到牌堆顶的卡片移到牌堆底
+fn put_on_bottom(commands: &mut Commands, deck: Entity, cards: Vec<Entity>) {
+    commands.queue(move |world: &mut World| {
+        let Some(zone) = world.get::<Zone>(deck).copied() else {
+            return;
+        };
+        let Some(mut deck_zone) = world.get_mut::<DeckZone>(deck) else {
+            return;
+        };
+        deck_zone.card_list.retain(|card| !cards.contains(card));
+        deck_zone.card_list.splice(0..0, cards);
+        let card_list = deck_zone.card_list.clone();
+        relayout_deck(world, &zone, &card_list);
+    });
+}
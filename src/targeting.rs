@@ -0,0 +1,331 @@
+use crate::prelude::{HandCardPlane, pointer_ray};
+use bevy::asset::RenderAssetUsages;
+use bevy::picking::backend::ray::RayMap;
+use bevy::picking::hover::HoverMap;
+use bevy::picking::pointer::PointerId;
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::view::NoFrustumCulling;
+
+/// 指向目标的箭头
+///
+/// 添加到任意实体上开始指向，选定或取消后实体会被删除
+#[derive(Component, Clone, Copy, Debug)]
+pub struct TargetingArrow {
+    /// 箭头的起点卡片
+    pub source: Entity,
+    /// 控制箭头的指针
+    pub pointer: PointerId,
+    /// 当前吸附的目标
+    pub hovered: Option<Entity>,
+}
+
+impl TargetingArrow {
+    pub fn new(source: Entity) -> Self {
+        Self {
+            source,
+            pointer: PointerId::Mouse,
+            hovered: None,
+        }
+    }
+}
+
+/// 指向规则
+///
+/// 实现在起点卡片的数据类型上，`T` 为目标上的数据类型
+pub trait TargetFilter<T>
+where
+    T: Component,
+{
+    /// 是否可以指向这个目标
+    fn can_target(&self, target: &T) -> bool;
+}
+
+/// 绑定指向规则
+pub fn bind_target_filter<S, T>(app: &mut App)
+where
+    S: Component + TargetFilter<T>,
+    T: Component,
+{
+    app.add_systems(Update, mark_valid_targets::<S, T>);
+}
+
+/// 当前箭头可以指向的目标
+#[derive(Component, Clone, Copy, Debug)]
+pub struct ValidTarget {
+    pub arrow: Entity,
+}
+
+/// 选定了目标
+#[derive(Event, Clone, Debug)]
+pub struct TargetChosen {
+    pub source: Entity,
+    pub target: Entity,
+}
+
+/// 取消了指向
+#[derive(Event, Clone, Debug)]
+pub struct TargetingCancelled {
+    pub source: Entity,
+}
+
+/// 箭头的样式
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct TargetingArrowConfig {
+    pub color: Color,
+    /// 可指向目标的边框颜色
+    pub target_color: Color,
+    pub width: f32,
+    /// 弯曲程度
+    pub curvature: f32,
+    /// 曲线分段数
+    pub segments: usize,
+}
+
+impl Default for TargetingArrowConfig {
+    fn default() -> Self {
+        Self {
+            color: Color::srgba(1.0, 0.3, 0.2, 0.9),
+            target_color: Color::srgb(1.0, 0.3, 0.2),
+            width: 0.4,
+            curvature: 0.25,
+            segments: 24,
+        }
+    }
+}
+
+pub struct TargetingPlugin;
+
+impl Plugin for TargetingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TargetingArrowConfig>()
+            .add_observer(clear_valid_targets)
+            .add_systems(
+                Update,
+                (
+                    added_targeting_arrow,
+                    update_targeting_arrow,
+                    finish_targeting,
+                )
+                    .chain(),
+            );
+    }
+}
+
+fn added_targeting_arrow(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    config: Res<TargetingArrowConfig>,
+    query: Query<Entity, Added<TargetingArrow>>,
+) {
+    for arrow_entity in query.iter() {
+        commands.entity(arrow_entity).insert((
+            Mesh3d(meshes.add(arrow_mesh(&[], config.width))),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: config.color,
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                double_sided: true,
+                cull_mode: None,
+                ..default()
+            })),
+            Transform::default(),
+            Visibility::default(),
+            // 网格每帧都会变化 包围盒不会更新
+            NoFrustumCulling,
+            Pickable::IGNORE,
+        ));
+    }
+}
+
+fn mark_valid_targets<S, T>(
+    mut commands: Commands,
+    query_arrow: Query<(Entity, &TargetingArrow), Added<TargetingArrow>>,
+    query_source: Query<&S>,
+    query_target: Query<(Entity, &T)>,
+) where
+    S: Component + TargetFilter<T>,
+    T: Component,
+{
+    for (arrow_entity, arrow) in query_arrow.iter() {
+        let Ok(source) = query_source.get(arrow.source) else {
+            continue;
+        };
+        for (target_entity, target) in query_target.iter() {
+            if target_entity != arrow.source && source.can_target(target) {
+                commands.entity(target_entity).insert(ValidTarget {
+                    arrow: arrow_entity,
+                });
+            }
+        }
+    }
+}
+
+/// 箭头跟随指针 经过可指向的目标时吸附
+#[allow(clippy::too_many_arguments)]
+fn update_targeting_arrow(
+    mut query_arrow: Query<(Entity, &mut TargetingArrow, &Mesh3d)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    config: Res<TargetingArrowConfig>,
+    ray_map: Res<RayMap>,
+    hover_map: Res<HoverMap>,
+    query_transform: Query<&GlobalTransform>,
+    query_valid: Query<&ValidTarget>,
+    query_parent: Query<&ChildOf>,
+    query_camera: Query<&Camera>,
+    plane: Single<&GlobalTransform, With<HandCardPlane>>,
+) {
+    for (arrow_entity, mut arrow, mesh) in query_arrow.iter_mut() {
+        let Ok(source_transform) = query_transform.get(arrow.source) else {
+            continue;
+        };
+        let is_valid = |entity: Entity| {
+            query_valid
+                .get(entity)
+                .is_ok_and(|valid| valid.arrow == arrow_entity)
+        };
+        // 命中的可能是卡片的子网格
+        let hovered = hover_map.get(&arrow.pointer).and_then(|hovered| {
+            hovered.keys().find_map(|entity| {
+                if is_valid(*entity) {
+                    return Some(*entity);
+                }
+                query_parent
+                    .get(*entity)
+                    .ok()
+                    .map(ChildOf::parent)
+                    .filter(|parent| is_valid(*parent))
+            })
+        });
+        if arrow.hovered != hovered {
+            arrow.hovered = hovered;
+        }
+
+        let end = if let Some(target_transform) =
+            hovered.and_then(|target| query_transform.get(target).ok())
+        {
+            target_transform.translation()
+        } else {
+            let Some(ray) = pointer_ray(&ray_map, &query_camera, arrow.pointer, None) else {
+                continue;
+            };
+            let Some(distance) =
+                ray.intersect_plane(plane.translation(), InfinitePlane3d::new(plane.up()))
+            else {
+                continue;
+            };
+            ray.get_point(distance)
+        };
+        let points = bezier_points(
+            source_transform.translation(),
+            end,
+            config.curvature,
+            config.segments,
+        );
+        if let Some(mesh) = meshes.get_mut(&mesh.0) {
+            *mesh = arrow_mesh(&points, config.width);
+        }
+    }
+}
+
+/// 左键确认 右键或 Esc 取消
+fn finish_targeting(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    query_arrow: Query<(Entity, Ref<TargetingArrow>)>,
+) {
+    let confirm = mouse.just_pressed(MouseButton::Left);
+    let cancel = mouse.just_pressed(MouseButton::Right) || keyboard.just_pressed(KeyCode::Escape);
+    if !confirm && !cancel {
+        return;
+    }
+    for (arrow_entity, arrow) in query_arrow.iter() {
+        // 开始指向的那次点击不算
+        if arrow.is_added() {
+            continue;
+        }
+        match arrow.hovered {
+            Some(target) if confirm && !cancel => {
+                commands.trigger(TargetChosen {
+                    source: arrow.source,
+                    target,
+                });
+            }
+            _ => {
+                commands.trigger(TargetingCancelled {
+                    source: arrow.source,
+                });
+            }
+        }
+        commands.entity(arrow_entity).despawn();
+    }
+}
+
+fn clear_valid_targets(
+    trigger: Trigger<OnRemove, TargetingArrow>,
+    mut commands: Commands,
+    query_valid: Query<(Entity, &ValidTarget)>,
+) {
+    for (entity, valid) in query_valid.iter() {
+        if valid.arrow == trigger.target() {
+            commands.entity(entity).remove::<ValidTarget>();
+        }
+    }
+}
+
+/// 二次贝塞尔曲线上的点 控制点在平面内偏向一侧
+fn bezier_points(start: Vec3, end: Vec3, curvature: f32, segments: usize) -> Vec<Vec3> {
+    let delta = end - start;
+    let side = delta.cross(Vec3::Z).normalize_or_zero();
+    let control = (start + end) / 2.0 + side * delta.length() * curvature;
+    (0..=segments.max(1))
+        .map(|i| {
+            let t = i as f32 / segments.max(1) as f32;
+            let u = 1.0 - t;
+            start * u * u + control * 2.0 * u * t + end * t * t
+        })
+        .collect()
+}
+
+/// 沿曲线生成带箭头的条带
+fn arrow_mesh(points: &[Vec3], width: f32) -> Mesh {
+    // 空的网格无法渲染 用一个退化的三角形占位
+    let mut positions: Vec<Vec3> = vec![Vec3::ZERO; 3];
+    let mut indices: Vec<u32> = vec![0, 1, 2];
+    if points.len() >= 3 {
+        positions.clear();
+        indices.clear();
+        let head_length = width * 2.0;
+        let body = &points[..points.len() - 1];
+        for (i, point) in body.iter().enumerate() {
+            let next = points[i + 1];
+            let side = (next - *point).cross(Vec3::Z).normalize_or_zero() * width / 2.0;
+            positions.push(*point + side);
+            positions.push(*point - side);
+            if i > 0 {
+                let base = (i as u32 - 1) * 2;
+                indices.extend([base, base + 1, base + 2, base + 1, base + 3, base + 2]);
+            }
+        }
+        // 箭头
+        let tip = points[points.len() - 1];
+        let last = points[points.len() - 2];
+        let direction = (tip - last).normalize_or_zero();
+        let side = direction.cross(Vec3::Z) * width;
+        let base = positions.len() as u32;
+        positions.push(tip - direction * head_length + side);
+        positions.push(tip - direction * head_length - side);
+        positions.push(tip);
+        indices.extend([base, base + 1, base + 2]);
+    }
+    let normals = vec![Vec3::Z; positions.len()];
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+    .with_inserted_indices(Indices::U32(indices))
+}
@@ -4,8 +4,10 @@ use crate::prelude::event::DeclareDraggingDoneForCard;
 use crate::zone::Zone;
 use bevy::prelude::*;
 use bevy_tween::combinator::{event, event_for, parallel, sequence, tween};
+use bevy_tween::interpolate::{Rotation, Scale, Translation};
 use bevy_tween::interpolation::EaseKind;
 use bevy_tween::prelude::{AnimationBuilderExt, IntoTarget, TransformTargetStateExt};
+use bevy_tween::tween::{ComponentTween, TargetComponent};
 use std::time::Duration;
 
 // 卡片移动回某个地方
@@ -185,3 +187,33 @@ pub fn card_set_on_zone_animation_without_boom(
             ),)),
         )));
 }
+
+/// 停止正在移动这些卡片的动画 同一个 TimeRunner 中的其他动画照常播放
+pub fn stop_card_animations(world: &mut World, cards: &[Entity]) {
+    stop_card_tweens::<Translation>(world, cards);
+    stop_card_tweens::<Rotation>(world, cards);
+    stop_card_tweens::<Scale>(world, cards);
+}
+
+/// 只作用于这些卡片的 tween 被删除 同时作用于其他实体的 tween 去掉这些卡片
+fn stop_card_tweens<I: Send + Sync + 'static>(world: &mut World, cards: &[Entity]) {
+    let mut despawned = vec![];
+    for (entity, mut tween) in world
+        .query::<(Entity, &mut ComponentTween<I>)>()
+        .iter_mut(world)
+    {
+        match &mut tween.target {
+            TargetComponent::Entity(target) if cards.contains(target) => despawned.push(entity),
+            TargetComponent::Entities(targets) if targets.iter().any(|e| cards.contains(e)) => {
+                targets.retain(|e| !cards.contains(e));
+                if targets.is_empty() {
+                    despawned.push(entity);
+                }
+            }
+            _ => {}
+        }
+    }
+    for entity in despawned {
+        world.entity_mut(entity).despawn();
+    }
+}
@@ -0,0 +1,519 @@
+use crate::card::card_state::{CardOrientation, CardState, calculate_transform};
+use crate::card3d::Card3DConfig;
+use crate::prelude::{Card, Dragged, Moveable};
+use crate::tween::animation::stop_card_animations;
+use crate::zone::Zone;
+use crate::zone::deck_zone::{DeckCard, DeckZone, relayout_deck};
+use crate::zone::desk_zone::{DeskCard, DeskZone, DeskZoneChangedEvent};
+use crate::zone::visibility::{CardView, LocalViewer, ZoneOwner, ZoneVisibility};
+use bevy::input::mouse::MouseWheel;
+use bevy::prelude::*;
+use bevy_tween::combinator::{
+    AnimationBuilderExt, TransformTargetStateExt, parallel, sequence, tween,
+};
+use bevy_tween::prelude::{EaseKind, IntoTarget};
+use std::time::Duration;
+
+/// 浏览场地的网格设置
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct ZoneBrowserConfig {
+    /// 每行的卡片数量
+    pub columns: usize,
+    /// 同时显示的行数 超出时用滚轮滚动
+    pub rows: usize,
+    /// 卡片之间的间隔
+    pub spacing: Vec2,
+    /// 网格离相机的距离
+    pub distance: f32,
+    /// 网格后面的背景
+    pub backdrop: Color,
+    /// 悬停时卡片靠近相机的距离
+    pub hover_lift: f32,
+}
+
+impl Default for ZoneBrowserConfig {
+    fn default() -> Self {
+        Self {
+            columns: 6,
+            rows: 2,
+            spacing: Vec2::splat(0.3),
+            distance: 12.0,
+            backdrop: Color::srgba(0.0, 0.0, 0.0, 0.6),
+            hover_lift: 0.5,
+        }
+    }
+}
+
+// 在相机前展开 DeskZone 或 DeckZone 中的所有卡片 同时只能浏览一个场地
+#[derive(Event, Clone, Copy, Debug)]
+pub struct OpenZoneBrowser {
+    pub zone: Entity,
+}
+
+// 关闭浏览 卡片回到场地中
+#[derive(Event, Clone, Copy, Debug)]
+pub struct CloseZoneBrowser;
+
+/// 正在浏览的场地 同时也是网格后面的背景
+#[derive(Component, Clone, Debug)]
+pub struct ZoneBrowser {
+    pub zone: Entity,
+    /// 最上面的卡片排在最前
+    pub cards: Vec<Entity>,
+    /// 滚动的行数
+    pub scroll: usize,
+    /// 网格所在的位置 在相机前方
+    pub frame: Transform,
+}
+
+/// 正在被浏览的卡片 记录浏览前的状态
+#[derive(Component, Clone, Debug)]
+pub struct BrowsedCard {
+    pub browser: Entity,
+    state: Option<CardState>,
+    moveable: bool,
+}
+
+pub struct ZoneBrowserPlugin;
+
+impl Plugin for ZoneBrowserPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ZoneBrowserConfig>()
+            .add_observer(open_zone_browser)
+            .add_observer(close_zone_browser)
+            .add_observer(desk_card_left)
+            .add_observer(deck_card_left)
+            .add_observer(on_browsed_card_over)
+            .add_observer(on_browsed_card_out)
+            .add_systems(
+                Update,
+                (
+                    scroll_zone_browser,
+                    update_zone_browser,
+                    hide_while_dragging,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn open_zone_browser(
+    trigger: Trigger<OpenZoneBrowser>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    query_browser: Query<Entity, With<ZoneBrowser>>,
+    query_zone: Query<(Option<&DeskZone>, Option<&DeckZone>), With<Zone>>,
+    query_visibility: Query<(&ZoneVisibility, Option<&ZoneOwner>)>,
+    query_card: Query<(Option<&CardState>, Has<Moveable>), With<Card>>,
+    query_browsed: Query<&BrowsedCard>,
+    query_camera: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+    viewer: Res<LocalViewer>,
+    config: Res<ZoneBrowserConfig>,
+    card3d_config: Res<Card3DConfig>,
+) {
+    let zone_entity = trigger.zone;
+    let Ok((opt_desk, opt_deck)) = query_zone.get(zone_entity) else {
+        return;
+    };
+    let Some(card_list) = opt_desk
+        .map(|desk| &desk.card_list)
+        .or(opt_deck.map(|deck| &deck.card_list))
+    else {
+        return;
+    };
+    let Some((_, camera_transform)) = query_camera
+        .iter()
+        .filter(|(camera, _)| camera.is_active)
+        .max_by_key(|(camera, _)| camera.order)
+    else {
+        return;
+    };
+    if !query_browser.is_empty() {
+        commands.trigger(CloseZoneBrowser);
+    }
+    // 卡片是否可以正面朝向玩家
+    let face_up = query_visibility
+        .get(zone_entity)
+        .map_or(true, |(visibility, opt_owner)| {
+            visibility.view(opt_owner.map(|owner| owner.0), viewer.0) != CardView::Back
+        });
+
+    let (_, rotation, translation) = camera_transform.to_scale_rotation_translation();
+    let frame = Transform::from_translation(translation + rotation * Vec3::NEG_Z * config.distance)
+        .with_rotation(rotation);
+    let cell = Vec2::new(card3d_config.width, card3d_config.height) + config.spacing;
+    let backdrop_size = cell * Vec2::new(config.columns as f32, config.rows as f32) + cell;
+    let cards: Vec<Entity> = card_list.iter().rev().copied().collect();
+    let browser = commands
+        .spawn((
+            Name::new(format!("zone browser of {:?}", zone_entity)),
+            Mesh3d(meshes.add(Rectangle::from_size(backdrop_size))),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: config.backdrop,
+                unlit: true,
+                alpha_mode: AlphaMode::Blend,
+                ..default()
+            })),
+            // 背景在卡片后面 不挡住拖拽
+            frame.with_translation(frame.translation + frame.forward() * 0.5),
+            Pickable::IGNORE,
+        ))
+        .id();
+    for card_entity in cards.iter() {
+        let Ok((opt_state, moveable)) = query_card.get(*card_entity) else {
+            continue;
+        };
+        // 重新浏览同一个场地时 使用最初的状态
+        let (state, moveable) = match query_browsed.get(*card_entity).ok() {
+            Some(browsed) => (browsed.state.clone(), browsed.moveable),
+            None => (opt_state.cloned(), moveable),
+        };
+        commands.entity(*card_entity).insert((
+            BrowsedCard {
+                browser,
+                state,
+                moveable,
+            },
+            CardState {
+                face_up,
+                orientation: CardOrientation::Upright,
+            },
+            Moveable,
+        ));
+    }
+    commands.entity(browser).insert(ZoneBrowser {
+        zone: zone_entity,
+        cards,
+        scroll: 0,
+        frame,
+    });
+}
+
+fn close_zone_browser(
+    _trigger: Trigger<CloseZoneBrowser>,
+    mut commands: Commands,
+    mut desk_zone_changed: EventWriter<DeskZoneChangedEvent>,
+    query_browser: Query<(Entity, &ZoneBrowser)>,
+    query_browsed: Query<(Entity, &BrowsedCard)>,
+    query_deck: Query<(&Zone, &DeckZone)>,
+) {
+    for (browser_entity, browser) in query_browser.iter() {
+        let cards: Vec<Entity> = query_browsed
+            .iter()
+            .filter(|(_, browsed)| browsed.browser == browser_entity)
+            .map(|(entity, _)| entity)
+            .collect();
+        commands.queue({
+            let cards = cards.clone();
+            move |world: &mut World| stop_card_animations(world, &cards)
+        });
+        for card_entity in cards {
+            let Ok((_, browsed)) = query_browsed.get(card_entity) else {
+                continue;
+            };
+            let mut entity_commands = commands.entity(card_entity);
+            entity_commands
+                .remove::<BrowsedCard>()
+                .insert(Visibility::Inherited);
+            match browsed.state.clone() {
+                Some(state) => entity_commands.insert(state),
+                None => entity_commands.remove::<CardState>(),
+            };
+            if !browsed.moveable {
+                entity_commands.remove::<Moveable>();
+            }
+        }
+        commands.entity(browser_entity).despawn();
+        // 回到场地中原来的位置
+        if let Ok((zone, deck)) = query_deck.get(browser.zone) {
+            let (zone, card_list) = (*zone, deck.card_list.clone());
+            commands.queue(move |world: &mut World| relayout_deck(world, &zone, &card_list));
+        } else {
+            desk_zone_changed.write(DeskZoneChangedEvent::Relayout { desk: browser.zone });
+        }
+    }
+}
+
+/// 被拖到别处的卡片不再属于浏览
+fn desk_card_left(
+    trigger: Trigger<OnReplace, DeskCard>,
+    query_card: Query<(&DeskCard, &BrowsedCard)>,
+    query_browser: Query<&ZoneBrowser>,
+    mut commands: Commands,
+) {
+    if let Ok((desk_card, browsed)) = query_card.get(trigger.target()) {
+        leave_browser(
+            trigger.target(),
+            desk_card.belongs_to_desk,
+            browsed,
+            &query_browser,
+            &mut commands,
+        );
+    }
+}
+
+fn deck_card_left(
+    trigger: Trigger<OnReplace, DeckCard>,
+    query_card: Query<(&DeckCard, &BrowsedCard)>,
+    query_browser: Query<&ZoneBrowser>,
+    mut commands: Commands,
+) {
+    if let Ok((deck_card, browsed)) = query_card.get(trigger.target()) {
+        leave_browser(
+            trigger.target(),
+            deck_card.belongs_to_deck,
+            browsed,
+            &query_browser,
+            &mut commands,
+        );
+    }
+}
+
+fn leave_browser(
+    card_entity: Entity,
+    opt_zone: Option<Entity>,
+    browsed: &BrowsedCard,
+    query_browser: &Query<&ZoneBrowser>,
+    commands: &mut Commands,
+) {
+    let from_browsed_zone = query_browser
+        .get(browsed.browser)
+        .is_ok_and(|browser| Some(browser.zone) == opt_zone);
+    if from_browsed_zone {
+        commands
+            .entity(card_entity)
+            .try_remove::<BrowsedCard>()
+            .try_insert(Visibility::Inherited);
+    }
+}
+
+fn scroll_zone_browser(
+    mut wheel: EventReader<MouseWheel>,
+    mut query_browser: Query<&mut ZoneBrowser>,
+    config: Res<ZoneBrowserConfig>,
+) {
+    let delta: f32 = wheel.read().map(|event| event.y).sum();
+    if delta == 0.0 {
+        return;
+    }
+    for mut browser in query_browser.iter_mut() {
+        let total_rows = browser.cards.len().div_ceil(config.columns.max(1));
+        let max_scroll = total_rows.saturating_sub(config.rows);
+        let scroll = if delta > 0.0 {
+            browser.scroll.saturating_sub(1)
+        } else {
+            (browser.scroll + 1).min(max_scroll)
+        };
+        if browser.scroll != scroll {
+            browser.scroll = scroll;
+        }
+    }
+}
+
+/// 场地中的卡片变化或滚动后 重新排列网格
+#[allow(clippy::too_many_arguments)]
+fn update_zone_browser(
+    mut commands: Commands,
+    mut query_browser: Query<(Entity, &mut ZoneBrowser)>,
+    mut query_card: Query<(&mut Card, &Transform, Option<&CardState>)>,
+    mut query_visibility: Query<&mut Visibility, With<BrowsedCard>>,
+    query_browsed: Query<&BrowsedCard>,
+    query_zone: Query<(), With<Zone>>,
+    config: Res<ZoneBrowserConfig>,
+    card3d_config: Res<Card3DConfig>,
+) {
+    for (browser_entity, mut browser) in query_browser.iter_mut() {
+        // 场地被删除时结束浏览
+        if !query_zone.contains(browser.zone) {
+            for card_entity in browser.cards.iter() {
+                commands
+                    .entity(*card_entity)
+                    .try_remove::<BrowsedCard>()
+                    .try_insert(Visibility::Inherited);
+            }
+            commands.entity(browser_entity).despawn();
+            continue;
+        }
+        let in_browser = |card_entity: &Entity| {
+            query_browsed
+                .get(*card_entity)
+                .is_ok_and(|browsed| browsed.browser == browser_entity)
+        };
+        if browser.cards.iter().all(in_browser) && !browser.is_changed() {
+            continue;
+        }
+        let cards: Vec<Entity> = browser.cards.iter().copied().filter(in_browser).collect();
+        let columns = config.columns.max(1);
+        let max_scroll = cards.len().div_ceil(columns).saturating_sub(config.rows);
+        let browser = browser.bypass_change_detection();
+        browser.cards = cards;
+        browser.scroll = browser.scroll.min(max_scroll);
+
+        let cell = Vec2::new(card3d_config.width, card3d_config.height) + config.spacing;
+        for (index, card_entity) in browser.cards.iter().enumerate() {
+            let Ok((mut card, transform, opt_state)) = query_card.get_mut(*card_entity) else {
+                continue;
+            };
+            let row = (index / columns) as f32 - browser.scroll as f32;
+            let column = (index % columns) as f32;
+            let local = Vec3::new(
+                (column - (columns as f32 - 1.0) / 2.0) * cell.x,
+                ((config.rows as f32 - 1.0) / 2.0 - row) * cell.y,
+                0.0,
+            );
+            let slot = browser.frame * Transform::from_translation(local);
+            // 滚动到网格外的卡片隐藏起来
+            let shown = row >= 0.0 && row < config.rows as f32;
+            if let Ok(mut visibility) = query_visibility.get_mut(*card_entity) {
+                *visibility = if shown {
+                    Visibility::Inherited
+                } else {
+                    Visibility::Hidden
+                };
+            }
+            card.origin = slot;
+            play_browse_animation(
+                *card_entity,
+                transform,
+                calculate_transform(slot, opt_state.cloned()),
+                &mut commands,
+            );
+        }
+    }
+}
+
+/// 拖拽浏览中的卡片时 隐藏网格露出下面的场地
+fn hide_while_dragging(
+    mut query_browser: Query<(Entity, &ZoneBrowser, &mut Visibility), Without<Card>>,
+    mut query_card: Query<&mut Visibility, With<Card>>,
+    query_dragged: Query<&Dragged>,
+    query_browsed: Query<&BrowsedCard>,
+    config: Res<ZoneBrowserConfig>,
+) {
+    for (browser_entity, browser, mut browser_visibility) in query_browser.iter_mut() {
+        let dragging = browser
+            .cards
+            .iter()
+            .any(|card_entity| matches!(query_dragged.get(*card_entity), Ok(Dragged::Actively)));
+        let wanted = if dragging {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+        if *browser_visibility == wanted {
+            continue;
+        }
+        *browser_visibility = wanted;
+        let columns = config.columns.max(1);
+        for (index, card_entity) in browser.cards.iter().enumerate() {
+            if !query_browsed
+                .get(*card_entity)
+                .is_ok_and(|browsed| browsed.browser == browser_entity)
+            {
+                continue;
+            }
+            let Ok(mut visibility) = query_card.get_mut(*card_entity) else {
+                continue;
+            };
+            let row = index / columns;
+            let shown = row >= browser.scroll && row < browser.scroll + config.rows;
+            *visibility = if shown && (!dragging || query_dragged.contains(*card_entity)) {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+        }
+    }
+}
+
+fn on_browsed_card_over(
+    over_trigger: Trigger<Pointer<Over>>,
+    mut commands: Commands,
+    query: Query<&ChildOf>,
+    query_card: Query<(&Transform, &Card, Option<&CardState>), With<BrowsedCard>>,
+    query_dragged: Query<(), With<Dragged>>,
+    config: Res<ZoneBrowserConfig>,
+) {
+    let Some((card_entity, (transform, card, opt_state))) = query
+        .get(over_trigger.target)
+        .ok()
+        .filter(|parent| !query_dragged.contains(parent.parent()))
+        .and_then(|parent| {
+            query_card
+                .get(parent.parent())
+                .ok()
+                .map(|card| (parent.parent(), card))
+        })
+    else {
+        return;
+    };
+    let mut end = card.origin;
+    end.translation += card.origin.rotation * Vec3::Z * config.hover_lift;
+    end.scale *= 1.15;
+    play_browse_animation(
+        card_entity,
+        transform,
+        calculate_transform(end, opt_state.cloned()),
+        &mut commands,
+    );
+}
+
+fn on_browsed_card_out(
+    out_trigger: Trigger<Pointer<Out>>,
+    mut commands: Commands,
+    query: Query<&ChildOf>,
+    query_card: Query<(&Transform, &Card, Option<&CardState>), With<BrowsedCard>>,
+    query_dragged: Query<(), With<Dragged>>,
+) {
+    let Some((card_entity, (transform, card, opt_state))) = query
+        .get(out_trigger.target)
+        .ok()
+        .filter(|parent| !query_dragged.contains(parent.parent()))
+        .and_then(|parent| {
+            query_card
+                .get(parent.parent())
+                .ok()
+                .map(|card| (parent.parent(), card))
+        })
+    else {
+        return;
+    };
+    play_browse_animation(
+        card_entity,
+        transform,
+        calculate_transform(card.origin, opt_state.cloned()),
+        &mut commands,
+    );
+}
+
+fn play_browse_animation(
+    card_entity: Entity,
+    transform: &Transform,
+    end: Transform,
+    commands: &mut Commands,
+) {
+    let target = card_entity.into_target();
+    let mut start = target.transform_state(*transform);
+    commands
+        .spawn(Name::new(format!("zone browser card {:?}", card_entity)))
+        .animation()
+        .insert(sequence((parallel((
+            tween(
+                Duration::from_secs_f32(0.2),
+                EaseKind::ExponentialOut,
+                start.translation_to(end.translation),
+            ),
+            tween(
+                Duration::from_secs_f32(0.2),
+                EaseKind::ExponentialOut,
+                start.rotation_to(end.rotation),
+            ),
+            tween(
+                Duration::from_secs_f32(0.2),
+                EaseKind::ExponentialOut,
+                start.scale_to(end.scale),
+            ),
+        )),)));
+}
@@ -0,0 +1,270 @@
+use crate::card::card_state::{CardOrientation, CardState, calculate_transform};
+use crate::card3d::Card3DConfig;
+use crate::prelude::Card;
+use crate::tween::animation::play_card_going_back_to_trans_animation;
+use crate::zone::label::ZoneLabel;
+use crate::zone::{Zone, send_card_to};
+use bevy::prelude::*;
+use bevy_tween::combinator::{
+    AnimationBuilderExt, TransformTargetStateExt, forward, sequence, tween,
+};
+use bevy_tween::prelude::{EaseKind, IntoTarget};
+use rand::seq::SliceRandom;
+use std::time::Duration;
+
+/// 牌堆中的卡片
+#[derive(Component, Clone, Debug)]
+pub struct DeckCard {
+    pub belongs_to_deck: Option<Entity>,
+}
+
+/// 牌堆 卡片背面朝上叠放，`card_list` 的最后一张在最上面
+///
+/// 牌堆上有 [`CardState`] 时卡片使用它的朝向 例如横着放的牌堆
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+pub struct DeckZone {
+    pub card_list: Vec<Entity>,
+    // 没有 ZoneLabel 时是否用 ZoneLabel 显示卡片数量
+    pub show_count: bool,
+}
+
+impl Default for DeckZone {
+    fn default() -> Self {
+        Self {
+            card_list: vec![],
+            show_count: true,
+        }
+    }
+}
+
+impl DeckZone {
+    /// 牌堆顶的卡片
+    pub fn top(&self) -> Option<Entity> {
+        self.card_list.last().copied()
+    }
+}
+
+// 从牌堆顶抽一张卡片 to 可以是 CardLine、DeskZone 或者 Zone
+#[derive(Event, Clone, Debug)]
+pub struct DrawCard {
+    pub deck: Entity,
+    pub to: Entity,
+}
+
+// 洗牌
+#[derive(Event, Clone, Debug)]
+pub struct ShuffleDeck {
+    pub deck: Entity,
+}
+
+// 抽到了卡片
+#[derive(Event, Clone, Debug)]
+pub struct CardDrawn {
+    pub deck: Entity,
+    pub card: Entity,
+    pub to: Entity,
+}
+
+pub struct DeckZonePlugin;
+
+impl Plugin for DeckZonePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<DeckZone>()
+            .add_observer(draw_card)
+            .add_observer(shuffle_deck)
+            .add_observer(removed_deck_card)
+            .add_systems(Update, (added_deck_card, show_deck_count));
+    }
+}
+
+/// 牌堆中卡片的姿态 背面朝上 朝向和牌堆一致
+fn deck_state(opt_deck_state: Option<&CardState>) -> CardState {
+    CardState {
+        face_up: false,
+        orientation: opt_deck_state.map_or(CardOrientation::Upright, |state| state.orientation),
+    }
+}
+
+/// 牌堆中第 index 张卡片的位置(不含姿态) 牌堆的厚度随数量增加
+///
+/// 卡片叠放在场地中心 横置时占用的大小不影响位置
+pub fn deck_card_slot(zone: &Zone, index: usize, card3d_config: &Card3DConfig) -> Transform {
+    let mut slot = zone.center;
+    slot.translation.z = (index + 1) as f32 * card3d_config.thick;
+    slot
+}
+
+/// 牌堆移动后 卡片回到新的位置
+pub fn relayout_deck(world: &mut World, zone: &Zone, card_list: &[Entity]) {
+    let card3d_config = *world.resource::<Card3DConfig>();
+    let mut moves = vec![];
+    for (index, card_entity) in card_list.iter().enumerate() {
+        let slot = deck_card_slot(zone, index, &card3d_config);
+        let Ok(mut entity) = world.get_entity_mut(*card_entity) else {
+            continue;
+        };
+        let opt_state = entity.get::<CardState>().cloned();
+        if let Some(mut card) = entity.get_mut::<Card>() {
+            card.origin = slot;
+        }
+        if let (Some(transform), Some(name)) = (entity.get::<Transform>(), entity.get::<Name>()) {
+            moves.push((
+                *card_entity,
+                calculate_transform(slot, opt_state),
+                *transform,
+                name.clone(),
+            ));
+        }
+    }
+    let mut commands = world.commands();
+    for (card_entity, end, transform, name) in moves {
+        play_card_going_back_to_trans_animation(card_entity, end, &transform, &name, &mut commands);
+    }
+}
+
+fn added_deck_card(
+    mut commands: Commands,
+    query: Query<(Entity, &DeckCard), Added<DeckCard>>,
+    mut query_deck: Query<(&Zone, &mut DeckZone, Option<&CardState>)>,
+    mut query_card: Query<(&mut Card, &Transform, &Name)>,
+    card3d_config: Res<Card3DConfig>,
+) {
+    for (card_entity, deck_card) in query.iter() {
+        let Some(deck_entity) = deck_card.belongs_to_deck else {
+            continue;
+        };
+        let Ok((zone, mut deck, opt_deck_state)) = query_deck.get_mut(deck_entity) else {
+            continue;
+        };
+        if !deck.card_list.contains(&card_entity) {
+            deck.card_list.push(card_entity);
+        }
+        let state = deck_state(opt_deck_state);
+        commands.entity(card_entity).insert(state.clone());
+        if let Ok((mut card, card_transform, card_name)) = query_card.get_mut(card_entity) {
+            let index = deck.card_list.len() - 1;
+            card.origin = deck_card_slot(zone, index, &card3d_config);
+            play_card_going_back_to_trans_animation(
+                card_entity,
+                calculate_transform(card.origin, Some(state)),
+                card_transform,
+                card_name,
+                &mut commands,
+            );
+        }
+    }
+}
+
+/// 离开牌堆的卡片
+fn removed_deck_card(
+    trigger: Trigger<OnRemove, DeckCard>,
+    query_card: Query<&DeckCard>,
+    mut query_deck: Query<&mut DeckZone>,
+) {
+    let Some(deck_entity) = query_card
+        .get(trigger.target())
+        .ok()
+        .and_then(|deck_card| deck_card.belongs_to_deck)
+    else {
+        return;
+    };
+    if let Ok(mut deck) = query_deck.get_mut(deck_entity) {
+        deck.card_list.retain(|x| *x != trigger.target());
+    }
+}
+
+fn draw_card(
+    trigger: Trigger<DrawCard>,
+    mut commands: Commands,
+    mut query_deck: Query<&mut DeckZone>,
+) {
+    let DrawCard { deck, to } = *trigger.event();
+    let Ok(mut deck_zone) = query_deck.get_mut(deck) else {
+        return;
+    };
+    let Some(card_entity) = deck_zone.card_list.pop() else {
+        debug!("deck {:?} is empty", deck);
+        return;
+    };
+    send_card_to(&mut commands, card_entity, to);
+    commands.trigger(CardDrawn {
+        deck,
+        card: card_entity,
+        to,
+    });
+}
+
+/// 洗牌：先把牌堆切成两半，再交错落回到新的顺序
+fn shuffle_deck(
+    trigger: Trigger<ShuffleDeck>,
+    mut commands: Commands,
+    mut query_deck: Query<(&Zone, &mut DeckZone, Option<&CardState>)>,
+    mut query_card: Query<(&mut Card, &Transform)>,
+    card3d_config: Res<Card3DConfig>,
+) {
+    let Ok((zone, mut deck, opt_deck_state)) = query_deck.get_mut(trigger.deck) else {
+        return;
+    };
+    let state = deck_state(opt_deck_state);
+    // 切开的两半按卡片占用的宽度分开
+    let width = state
+        .orientation
+        .footprint(Vec2::new(card3d_config.width, card3d_config.height))
+        .x;
+    let old_list = deck.card_list.clone();
+    deck.card_list.shuffle(&mut rand::rng());
+    let half = old_list.len() / 2;
+    for (new_index, card_entity) in deck.card_list.iter().enumerate() {
+        let Ok((mut card, card_transform)) = query_card.get_mut(*card_entity) else {
+            continue;
+        };
+        let old_index = old_list
+            .iter()
+            .position(|x| x == card_entity)
+            .unwrap_or_default();
+        // 切牌 上半和下半分到两侧
+        let side = if old_index < half { -1.0 } else { 1.0 };
+        let mut cut = calculate_transform(
+            deck_card_slot(zone, old_index, &card3d_config),
+            Some(state.clone()),
+        );
+        cut.translation += zone.center.rotation * Vec3::X * side * width * 0.6;
+        card.origin = deck_card_slot(zone, new_index, &card3d_config);
+        let end = calculate_transform(card.origin, Some(state.clone()));
+
+        let target = card_entity.into_target();
+        let mut start = target.transform_state(*card_transform);
+        commands
+            .spawn(Name::new(format!(
+                "shuffle deck card at index {}",
+                new_index
+            )))
+            .animation()
+            .insert(sequence((
+                tween(
+                    Duration::from_secs_f32(0.2),
+                    EaseKind::QuadraticOut,
+                    start.translation_to(cut.translation),
+                ),
+                // 交错落下
+                forward(Duration::from_secs_f32(0.01 * new_index as f32)),
+                tween(
+                    Duration::from_secs_f32(0.15),
+                    EaseKind::QuadraticIn,
+                    start.translation_to(end.translation),
+                ),
+            )));
+    }
+}
+
+/// 没有标签的牌堆用 [`ZoneLabel`] 显示卡片数量
+fn show_deck_count(mut commands: Commands, query: Query<(Entity, &DeckZone), Added<DeckZone>>) {
+    for (deck_entity, deck) in query.iter() {
+        if deck.show_count {
+            commands
+                .entity(deck_entity)
+                .insert_if_new(ZoneLabel::new("{count}"));
+        }
+    }
+}
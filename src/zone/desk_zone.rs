@@ -1,13 +1,20 @@
-use crate::card::card_state::{CardState, calculate_transform};
+use crate::card::card_state::{CardOrientation, CardState, calculate_transform};
 use crate::card3d::Card3DConfig;
-use crate::prelude::{Card, HandCard, Moveable};
-use crate::tween::animation::play_card_going_back_to_trans_animation;
-use crate::zone::Zone;
+use crate::prelude::attach::{Attachments, HostLeave};
+use crate::prelude::{Card, Dragged, GroupDragOffset, HandCard, Moveable};
+use crate::tween::animation::play_card_going_back_to_place_animation;
+use crate::zone::browse::BrowsedCard;
+use crate::zone::drop_filter::{DropFeedback, find_card};
+use crate::zone::events::{ZoneRejectReason, ZoneRejected};
+use crate::zone::layout::{ZoneLayout, ZoneLayoutContext, ZoneLayouts};
+use crate::zone::{Zone, drag_group};
+use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
 use bevy_tween::combinator::{
     AnimationBuilderExt, TransformTargetStateExt, parallel, sequence, tween,
 };
 use bevy_tween::prelude::{EaseKind, IntoTarget};
+use serde::Deserialize;
 use std::time::Duration;
 
 #[derive(Component, Clone, Debug)]
@@ -21,12 +28,53 @@ pub struct DeskZone {
     pub card_list: Vec<Entity>,
     // 平铺容量
     pub opt_capacity: Option<usize>,
+    // 排列方式
+    pub layout: ZoneLayout,
+    // 超出容量时的处理
+    pub overflow: ZoneOverflow,
+}
+
+/// 卡片数量超过容量时的处理
+///
+/// 排列时是否压缩间距由 [`ZoneLayout`] 决定
+#[derive(Clone, Copy, Debug, Default, PartialEq, Reflect, Deserialize)]
+pub enum ZoneOverflow {
+    /// 继续放入，按排列方式超出场地范围
+    #[default]
+    Overflow,
+    /// 拒绝放入，卡片回到原来的位置并触发 [`ZoneRejected`]
+    Reject,
+}
+
+impl DeskZone {
+    /// 是否已经放满 只有 [`ZoneOverflow::Reject`] 的场地会放满
+    pub fn is_full(&self) -> bool {
+        self.overflow == ZoneOverflow::Reject
+            && self
+                .opt_capacity
+                .is_some_and(|capacity| self.card_list.len() >= capacity)
+    }
+
+    /// 能否再放下这些卡片 已经在场地中的卡片不占用新的位置
+    pub fn has_room_for(&self, cards: &[Entity]) -> bool {
+        let adding = cards
+            .iter()
+            .filter(|card| !self.card_list.contains(card))
+            .count();
+        self.overflow != ZoneOverflow::Reject
+            || adding == 0
+            || self
+                .opt_capacity
+                .is_none_or(|capacity| self.card_list.len() + adding <= capacity)
+    }
 }
 
 #[derive(Event, Clone, Debug)]
 pub enum DeskZoneChangedEvent {
     Added { desk: Entity, card: Entity },
     Removed { desk: Entity, card: Entity },
+    // 重新排列场地中的卡片 例如修改了场地的位置或排列方式
+    Relayout { desk: Entity },
 }
 
 pub struct DeskZonePlugin;
@@ -34,18 +82,81 @@ pub struct DeskZonePlugin;
 impl Plugin for DeskZonePlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<DeskZoneChangedEvent>();
+        app.init_resource::<ZoneLayouts>();
         app.add_systems(Update, added_desk_card);
+        app.add_observer(reject_full_desk_zone);
         app.add_systems(PostUpdate, change_desk_cards_event);
     }
 }
 
+/// 拖拽经过放不下的场地时显示不能放下
+fn reject_full_desk_zone(
+    drag_enter: Trigger<Pointer<DragEnter>>,
+    mut commands: Commands,
+    query_desk_zone: Query<&DeskZone>,
+    query_group: Query<(Entity, &GroupDragOffset)>,
+    query_card: Query<Entity, With<Card>>,
+    query_parent: Query<&ChildOf>,
+) {
+    let Ok(desk_zone) = query_desk_zone.get(drag_enter.target()) else {
+        return;
+    };
+    let Some(card) = find_card(drag_enter.dragged, &query_card, &query_parent) else {
+        return;
+    };
+    if !desk_zone.has_room_for(&drag_group(card, &query_group)) {
+        commands
+            .entity(drag_enter.target())
+            .insert(DropFeedback::Reject { card });
+    }
+}
+
 fn added_desk_card(
     mut desk_card_event: EventWriter<DeskZoneChangedEvent>,
     mut commands: Commands,
     query: Query<(Entity, &DeskCard), (With<Card>, Added<DeskCard>)>,
+    query_desk_zone: Query<&DeskZone>,
+    query_card: Query<(&Card, &Transform, &Name, Option<&CardState>)>,
+    query_dragged: Query<(), With<Dragged>>,
 ) {
+    // 同一帧加入同一个场地的卡片数量
+    let mut pending: HashMap<Entity, usize> = HashMap::new();
     for (card_entity, desk_card) in query.iter() {
         if let Some(belongs_to_desk) = desk_card.belongs_to_desk {
+            if let Ok(desk_zone) = query_desk_zone.get(belongs_to_desk) {
+                let adding = pending.entry(belongs_to_desk).or_default();
+                let capacity = desk_zone.opt_capacity;
+                let full = desk_zone.overflow == ZoneOverflow::Reject
+                    && !desk_zone.card_list.contains(&card_entity)
+                    && capacity
+                        .is_some_and(|capacity| desk_zone.card_list.len() + *adding >= capacity);
+                if full {
+                    commands.entity(card_entity).remove::<DeskCard>();
+                    // 拖拽中的卡片松开后会自己回去
+                    if let (Ok((card, transform, name, opt_state)), false) = (
+                        query_card.get(card_entity),
+                        query_dragged.contains(card_entity),
+                    ) {
+                        play_card_going_back_to_place_animation(
+                            card_entity,
+                            card,
+                            transform,
+                            name,
+                            opt_state.cloned(),
+                            &mut commands,
+                        );
+                    }
+                    commands.trigger(ZoneRejected {
+                        zone: belongs_to_desk,
+                        card: card_entity,
+                        reason: ZoneRejectReason::Full {
+                            capacity: capacity.unwrap_or_default(),
+                        },
+                    });
+                    continue;
+                }
+                *adding += 1;
+            }
             commands.entity(card_entity).remove::<CardState>();
             desk_card_event.write(DeskZoneChangedEvent::Added {
                 desk: belongs_to_desk,
@@ -55,12 +166,17 @@ fn added_desk_card(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn change_desk_cards_event(
     mut commands: Commands,
     mut desk_card_changed: EventReader<DeskZoneChangedEvent>,
     mut query_desk_zone: Query<(&Zone, &mut DeskZone, Option<&CardState>)>,
     mut query_card: Query<(&mut Card, Option<&mut Transform>, Option<&CardState>)>,
+    query_desk_card: Query<&DeskCard>,
+    query_host: Query<(), With<Attachments>>,
+    query_browsed: Query<(), With<BrowsedCard>>,
     card3d_config: Res<Card3DConfig>,
+    layouts: Res<ZoneLayouts>,
 ) {
     for event in desk_card_changed.read() {
         match event {
@@ -74,22 +190,50 @@ pub fn change_desk_cards_event(
                         &desk_zone,
                         &mut commands,
                         &mut query_card,
+                        &query_browsed,
                         opt_state.cloned(),
                         card3d_config.clone(),
+                        &layouts,
                     );
                 }
             }
             DeskZoneChangedEvent::Removed { desk, card } => {
                 if let Ok((zone, mut desk_zone, opt_state)) = query_desk_zone.get_mut(*desk) {
                     desk_zone.card_list.retain(|x| *x != *card);
-                    commands.entity(*card).remove::<DeskCard>();
+                    // 卡片可能已经去了别的场地
+                    if query_desk_card
+                        .get(*card)
+                        .is_ok_and(|desk_card| desk_card.belongs_to_desk == Some(*desk))
+                    {
+                        commands.entity(*card).remove::<DeskCard>();
+                    }
+                    // 附着的卡片不跟随离开场地的宿主
+                    if query_host.contains(*card) {
+                        commands.trigger(HostLeave { host: *card });
+                    }
                     change_desk_cards_transform(
                         zone,
                         &desk_zone,
                         &mut commands,
                         &mut query_card,
+                        &query_browsed,
                         opt_state.cloned(),
                         card3d_config.clone(),
+                        &layouts,
+                    );
+                }
+            }
+            DeskZoneChangedEvent::Relayout { desk } => {
+                if let Ok((zone, desk_zone, opt_state)) = query_desk_zone.get(*desk) {
+                    change_desk_cards_transform(
+                        zone,
+                        desk_zone,
+                        &mut commands,
+                        &mut query_card,
+                        &query_browsed,
+                        opt_state.cloned(),
+                        *card3d_config,
+                        &layouts,
                     );
                 }
             }
@@ -97,43 +241,88 @@ pub fn change_desk_cards_event(
     }
 }
 
+/// 计算场地中第 index 张卡片的位置(不含姿态)
+///
+/// `orientations` 为参与排列的每张卡片的朝向，`current` 为卡片当前的位置
+pub fn desk_card_slot(
+    zone: &Zone,
+    desk_zone: &DeskZone,
+    index: usize,
+    orientations: &[CardOrientation],
+    current: Option<&Transform>,
+    card3d_config: &Card3DConfig,
+    layouts: &ZoneLayouts,
+) -> Transform {
+    let context = ZoneLayoutContext {
+        zone,
+        desk_zone,
+        card3d_config,
+        count: orientations.len(),
+        orientations,
+    };
+    desk_zone.layout.slot(&context, index, current, layouts)
+}
+
+/// 场地中卡片的朝向 卡片没有姿态时使用场地的姿态
+pub(crate) fn desk_card_orientation(
+    opt_card_state: Option<&CardState>,
+    opt_zone_state: Option<&CardState>,
+) -> CardOrientation {
+    opt_card_state
+        .or(opt_zone_state)
+        .map_or(CardOrientation::Upright, |state| state.orientation)
+}
+
+#[allow(clippy::too_many_arguments)]
 fn change_desk_cards_transform(
     zone: &Zone,
     desk_zone: &DeskZone,
     commands: &mut Commands,
     query_card: &mut Query<(&mut Card, Option<&mut Transform>, Option<&CardState>)>,
+    query_browsed: &Query<(), With<BrowsedCard>>,
     opt_state: Option<CardState>,
     card3d_config: Card3DConfig,
+    layouts: &ZoneLayouts,
 ) {
     if desk_zone.card_list.len() == 0 {
         return;
     }
+    let orientations: Vec<CardOrientation> = desk_zone
+        .card_list
+        .iter()
+        .map(|card_entity| {
+            let opt_card_state = query_card
+                .get(*card_entity)
+                .ok()
+                .and_then(|(_, _, opt_card_state)| opt_card_state);
+            desk_card_orientation(opt_card_state, opt_state.as_ref())
+        })
+        .collect();
     desk_zone
         .card_list
         .iter()
         .enumerate()
         .for_each(|(index, card_entity)| {
+            // 浏览中的卡片留在网格里
+            if query_browsed.contains(*card_entity) {
+                return;
+            }
             if let Ok((mut card, opt_card_transform, opt_card_state)) =
                 query_card.get_mut(*card_entity)
             {
                 let target = card_entity.clone().into_target();
-                let mut start = if let Some(card_transform) = opt_card_transform {
-                    target.transform_state(*card_transform)
-                } else {
-                    target.transform_state(card.origin)
-                };
+                let current = opt_card_transform.map_or(card.origin, |transform| *transform);
+                let mut start = target.transform_state(current);
 
-                let mut end = zone.center.clone();
-                end.translation.z = (index + 1) as f32 * card3d_config.thick;
-                // 进行平铺！
-                if let Some(capacity) = desk_zone.opt_capacity {
-                    let height = zone.size.y;
-                    let half_height = height / 2.0;
-                    let per = height / capacity as f32;
-                    let half_per = per / 2.0;
-                    end.translation.y =
-                        end.translation.y - half_height + half_per + index as f32 * per;
-                }
+                let end = desk_card_slot(
+                    zone,
+                    desk_zone,
+                    index,
+                    &orientations,
+                    Some(&current),
+                    &card3d_config,
+                    layouts,
+                );
                 let calculated_end = if opt_card_state.is_some() {
                     info!("Has old state {:?}", opt_card_state);
                     calculate_transform(end.clone(), opt_card_state.cloned())
@@ -146,7 +335,7 @@ fn change_desk_cards_transform(
                 };
 
                 // 修改这里的值
-                card.origin = Transform::from_translation(end.translation);
+                card.origin = end;
                 info!(
                     "change from {:?} to {:?} state{:?}",
                     card.origin, calculated_end, opt_state
@@ -174,3 +363,54 @@ fn change_desk_cards_transform(
             }
         });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(index: u32) -> Entity {
+        Entity::from_raw(index)
+    }
+
+    /// 容量为 3 已经放了两张卡片
+    fn desk_zone(overflow: ZoneOverflow) -> DeskZone {
+        DeskZone {
+            card_list: vec![card(1), card(2)],
+            opt_capacity: Some(3),
+            overflow,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn reject_zone_counts_whole_group() {
+        let desk_zone = desk_zone(ZoneOverflow::Reject);
+        assert!(desk_zone.has_room_for(&[card(3)]));
+        assert!(!desk_zone.has_room_for(&[card(3), card(4)]));
+    }
+
+    #[test]
+    fn cards_already_in_zone_take_no_room() {
+        let desk_zone = desk_zone(ZoneOverflow::Reject);
+        assert!(desk_zone.has_room_for(&[card(1), card(2), card(3)]));
+        assert!(!desk_zone.has_room_for(&[card(1), card(3), card(4)]));
+        // 放满后仍然可以在场地内移动
+        let mut full = desk_zone;
+        full.card_list.push(card(3));
+        assert!(full.is_full());
+        assert!(full.has_room_for(&[card(2), card(3)]));
+        assert!(!full.has_room_for(&[card(4)]));
+    }
+
+    #[test]
+    fn overflow_zone_always_has_room() {
+        let desk_zone = desk_zone(ZoneOverflow::Overflow);
+        assert!(desk_zone.has_room_for(&[card(3), card(4), card(5)]));
+        assert!(!desk_zone.is_full());
+        let unlimited = DeskZone {
+            overflow: ZoneOverflow::Reject,
+            ..default()
+        };
+        assert!(unlimited.has_room_for(&[card(3), card(4), card(5)]));
+    }
+}
@@ -0,0 +1,161 @@
+use crate::prelude::{Card, CardLine, HandCard};
+use crate::tween::animation::stop_card_animations;
+use crate::zone::deck_zone::{DeckCard, DeckZone};
+use crate::zone::desk_zone::{DeskCard, DeskZone};
+use crate::zone::send_card_to;
+use crate::zone::visibility::{PlayerId, ZoneOwner};
+use bevy::prelude::*;
+
+/// 场地、牌堆或手牌被删除时其中卡片的去向
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub enum OnZoneDespawn {
+    /// 留在原地 不再属于任何场地
+    #[default]
+    Release,
+    /// 一起删除
+    Despawn,
+    /// 送到另一个手牌、场地或牌堆
+    SendTo(Entity),
+    /// 回到主人的手牌 需要 [`ZoneOwner`]，找不到手牌时留在原地
+    ReturnToOwnerHand,
+}
+
+pub struct ZoneDespawnPlugin;
+
+impl Plugin for ZoneDespawnPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(desk_zone_removed)
+            .add_observer(deck_zone_removed)
+            .add_observer(card_line_removed);
+    }
+}
+
+fn desk_zone_removed(
+    trigger: Trigger<OnRemove, DeskZone>,
+    mut commands: Commands,
+    query: Query<(&DeskZone, Option<&OnZoneDespawn>, Option<&ZoneOwner>)>,
+) {
+    if let Ok((desk_zone, opt_policy, opt_owner)) = query.get(trigger.target()) {
+        release_cards(
+            &mut commands,
+            trigger.target(),
+            desk_zone.card_list.clone(),
+            opt_policy.copied().unwrap_or_default(),
+            opt_owner.map(|owner| owner.0),
+        );
+    }
+}
+
+fn deck_zone_removed(
+    trigger: Trigger<OnRemove, DeckZone>,
+    mut commands: Commands,
+    query: Query<(&DeckZone, Option<&OnZoneDespawn>, Option<&ZoneOwner>)>,
+) {
+    if let Ok((deck_zone, opt_policy, opt_owner)) = query.get(trigger.target()) {
+        release_cards(
+            &mut commands,
+            trigger.target(),
+            deck_zone.card_list.clone(),
+            opt_policy.copied().unwrap_or_default(),
+            opt_owner.map(|owner| owner.0),
+        );
+    }
+}
+
+fn card_line_removed(
+    trigger: Trigger<OnRemove, CardLine>,
+    mut commands: Commands,
+    query: Query<(&CardLine, Option<&OnZoneDespawn>, Option<&ZoneOwner>)>,
+) {
+    if let Ok((card_line, opt_policy, opt_owner)) = query.get(trigger.target()) {
+        release_cards(
+            &mut commands,
+            trigger.target(),
+            card_line.card_list.clone(),
+            opt_policy.copied().unwrap_or_default(),
+            opt_owner.map(|owner| owner.0),
+        );
+    }
+}
+
+/// 停止卡片的动画 去掉卡片和原来位置的关系 再按照规则处理
+fn release_cards(
+    commands: &mut Commands,
+    container: Entity,
+    cards: Vec<Entity>,
+    policy: OnZoneDespawn,
+    opt_owner: Option<PlayerId>,
+) {
+    commands.queue(move |world: &mut World| {
+        // 卡片可能已经去了别的地方
+        let cards: Vec<Entity> = cards
+            .into_iter()
+            .filter(|card| leave_container(world, *card, container))
+            .collect();
+        stop_card_animations(world, &cards);
+        let opt_to = match policy {
+            OnZoneDespawn::Release => None,
+            OnZoneDespawn::Despawn => {
+                for card in cards {
+                    world.entity_mut(card).despawn();
+                }
+                return;
+            }
+            OnZoneDespawn::SendTo(to) => Some(to),
+            OnZoneDespawn::ReturnToOwnerHand => opt_owner.and_then(|owner| {
+                world
+                    .query_filtered::<(Entity, &ZoneOwner), With<CardLine>>()
+                    .iter(world)
+                    .find(|(entity, zone_owner)| *entity != container && zone_owner.0 == owner)
+                    .map(|(entity, _)| entity)
+            }),
+        }
+        .filter(|to| world.get_entity(*to).is_ok());
+
+        for card in cards {
+            match opt_to {
+                Some(to) => send_card_to(&mut world.commands(), card, to),
+                None => {
+                    // 留在现在的位置
+                    let mut entity = world.entity_mut(card);
+                    let Some(transform) = entity.get::<Transform>().copied() else {
+                        continue;
+                    };
+                    if let Some(mut card) = entity.get_mut::<Card>() {
+                        card.origin = transform;
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// 去掉卡片和容器的关系 卡片不属于这个容器时返回 false
+fn leave_container(world: &mut World, card: Entity, container: Entity) -> bool {
+    let Ok(mut entity) = world.get_entity_mut(card) else {
+        return false;
+    };
+    let mut belonged = false;
+    if entity
+        .get::<HandCard>()
+        .is_some_and(|hand_card| hand_card.belong_to_card_line == Some(container))
+    {
+        entity.remove::<HandCard>();
+        belonged = true;
+    }
+    if entity
+        .get::<DeskCard>()
+        .is_some_and(|desk_card| desk_card.belongs_to_desk == Some(container))
+    {
+        entity.remove::<DeskCard>();
+        belonged = true;
+    }
+    if entity
+        .get::<DeckCard>()
+        .is_some_and(|deck_card| deck_card.belongs_to_deck == Some(container))
+    {
+        entity.remove::<DeckCard>();
+        belonged = true;
+    }
+    belonged
+}
@@ -0,0 +1,202 @@
+use crate::prelude::{Card, GroupDragOffset};
+use crate::zone::desk_zone::DeskZone;
+use crate::zone::{OwnZoneMaterial, Zone, drag_group, own_zone_material};
+use bevy::prelude::*;
+
+/// 场地的放置规则
+///
+/// 实现在场地的数据类型上，`C` 为卡片上的数据类型
+pub trait DropFilter<C>
+where
+    C: Component,
+{
+    /// 是否接受这张卡片
+    fn accept(&self, card: &C) -> bool;
+}
+
+/// 绑定场地的放置规则
+pub fn bind_drop_filter<Z, C>(app: &mut App)
+where
+    Z: Component + DropFilter<C>,
+    C: Component,
+{
+    app.add_observer(on_drag_enter_zone::<Z, C>);
+}
+
+/// 拖拽经过场地时的判定结果
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DropFeedback {
+    /// 可以放下
+    Accept { card: Entity },
+    /// 不能放下
+    Reject { card: Entity },
+}
+
+impl DropFeedback {
+    pub fn card(&self) -> Entity {
+        match self {
+            DropFeedback::Accept { card } | DropFeedback::Reject { card } => *card,
+        }
+    }
+
+    pub fn is_accept(&self) -> bool {
+        matches!(self, DropFeedback::Accept { .. })
+    }
+}
+
+/// 拖拽反馈的样式
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct DropFeedbackConfig {
+    /// 可放置场地的高亮颜色
+    pub accept_color: Color,
+    /// 不可放置场地的亮度系数
+    pub reject_dim: f32,
+}
+
+impl Default for DropFeedbackConfig {
+    fn default() -> Self {
+        Self {
+            accept_color: Color::srgb(0.2, 1.0, 0.4),
+            reject_dim: 0.3,
+        }
+    }
+}
+
+/// 被变暗前的颜色
+#[derive(Component, Clone, Copy, Debug)]
+struct DimmedFrom(Color);
+
+pub struct DropFilterPlugin;
+
+impl Plugin for DropFilterPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DropFeedbackConfig>()
+            .add_observer(on_drag_leave_zone)
+            .add_observer(show_drop_feedback)
+            .add_observer(clear_drop_feedback);
+    }
+}
+
+/// 找到子网格所属的卡片
+pub(crate) fn find_card(
+    entity: Entity,
+    query_card: &Query<Entity, With<Card>>,
+    query_parent: &Query<&ChildOf>,
+) -> Option<Entity> {
+    if let Ok(card) = query_card.get(entity) {
+        return Some(card);
+    }
+    query_parent
+        .get(entity)
+        .ok()
+        .and_then(|parent| query_card.get(parent.parent()).ok())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn on_drag_enter_zone<Z, C>(
+    drag_enter: Trigger<Pointer<DragEnter>>,
+    mut commands: Commands,
+    query_zone: Query<&Z, With<Zone>>,
+    query_card_data: Query<&C, With<Card>>,
+    query_card: Query<Entity, With<Card>>,
+    query_parent: Query<&ChildOf>,
+    query_desk_zone: Query<&DeskZone>,
+    query_group: Query<(Entity, &GroupDragOffset)>,
+) where
+    Z: Component + DropFilter<C>,
+    C: Component,
+{
+    let Ok(filter) = query_zone.get(drag_enter.target()) else {
+        return;
+    };
+    let Some(card) = find_card(drag_enter.dragged, &query_card, &query_parent) else {
+        return;
+    };
+    if !query_card_data.contains(card) {
+        return;
+    }
+    // 一起拖拽的卡片都要符合放置规则 有一张不符合就整组不能放下
+    let cards = drag_group(card, &query_group);
+    let accept = cards.iter().all(|card| {
+        query_card_data
+            .get(*card)
+            .ok()
+            .is_none_or(|card_data| filter.accept(card_data))
+    });
+    // 放满的场地由 DeskZonePlugin 显示不能放下
+    let full = query_desk_zone
+        .get(drag_enter.target())
+        .is_ok_and(|desk_zone| !desk_zone.has_room_for(&cards));
+    let feedback = if accept && !full {
+        DropFeedback::Accept { card }
+    } else {
+        DropFeedback::Reject { card }
+    };
+    debug!("drop feedback {:?} on {:?}", feedback, drag_enter.target());
+    commands.entity(drag_enter.target()).insert(feedback);
+}
+
+fn on_drag_leave_zone(
+    drag_leave: Trigger<Pointer<DragLeave>>,
+    mut commands: Commands,
+    query_zone: Query<(), (With<Zone>, With<DropFeedback>)>,
+) {
+    if query_zone.get(drag_leave.target()).is_ok() {
+        commands
+            .entity(drag_leave.target())
+            .remove::<DropFeedback>();
+    }
+}
+
+fn show_drop_feedback(
+    trigger: Trigger<OnInsert, DropFeedback>,
+    mut commands: Commands,
+    config: Res<DropFeedbackConfig>,
+    query_zone: Query<(
+        &DropFeedback,
+        &MeshMaterial3d<StandardMaterial>,
+        Option<&DimmedFrom>,
+    )>,
+    query_owned: Query<(), With<OwnZoneMaterial>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let zone_entity = trigger.target();
+    let Ok((feedback, material, opt_dimmed)) = query_zone.get(zone_entity) else {
+        return;
+    };
+    // 可以放下时的高亮由 ZoneHighlightPlugin 显示
+    if feedback.is_accept() {
+        return;
+    }
+    if let Some(material) = own_zone_material(
+        &mut commands,
+        zone_entity,
+        &material.0,
+        query_owned.contains(zone_entity),
+        &mut materials,
+    ) {
+        let origin = opt_dimmed.map_or(material.base_color, |dimmed| dimmed.0);
+        material.base_color = origin.mix(
+            &Color::BLACK.with_alpha(origin.alpha()),
+            1.0 - config.reject_dim,
+        );
+        commands.entity(zone_entity).insert(DimmedFrom(origin));
+    }
+}
+
+fn clear_drop_feedback(
+    trigger: Trigger<OnReplace, DropFeedback>,
+    mut commands: Commands,
+    query_zone: Query<(&MeshMaterial3d<StandardMaterial>, Option<&DimmedFrom>)>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let Ok((material, opt_dimmed)) = query_zone.get(trigger.target()) else {
+        return;
+    };
+    if let Some(dimmed) = opt_dimmed {
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.base_color = dimmed.0;
+        }
+        commands.entity(trigger.target()).remove::<DimmedFrom>();
+    }
+}
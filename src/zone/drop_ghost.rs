@@ -0,0 +1,262 @@
+use crate::card::card_state::{CardOrientation, CardState, calculate_transform};
+use crate::card3d::Card3DConfig;
+use crate::prelude::Card;
+use crate::zone::Zone;
+use crate::zone::desk_zone::{DeskZone, desk_card_orientation, desk_card_slot};
+use crate::zone::drop_filter::{DropFeedback, find_card};
+use crate::zone::layout::ZoneLayouts;
+use crate::zone::slots::{ZoneSlots, choose_slots};
+use bevy::prelude::*;
+
+/// 拖拽经过场地时 显示卡片落点的虚影
+#[derive(Component, Clone, Copy, Debug)]
+pub struct DropGhost {
+    pub zone: Entity,
+    pub card: Entity,
+    /// 指针在场地上的位置 有格子的场地按它选择格子
+    pub point: Option<Vec3>,
+}
+
+/// 虚影的样式
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct DropGhostConfig {
+    pub color: Color,
+}
+
+impl Default for DropGhostConfig {
+    fn default() -> Self {
+        Self {
+            color: Color::WHITE.with_alpha(0.35),
+        }
+    }
+}
+
+/// 卡片的姿态和位置
+type CardPose = (Option<&'static CardState>, &'static Transform);
+
+pub struct DropGhostPlugin;
+
+impl Plugin for DropGhostPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DropGhostConfig>()
+            .add_observer(spawn_drop_ghost)
+            .add_observer(move_drop_ghost)
+            .add_observer(despawn_drop_ghost_on_leave)
+            .add_observer(despawn_drop_ghost_on_end)
+            .add_systems(Update, place_drop_ghost);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_drop_ghost(
+    drag_enter: Trigger<Pointer<DragEnter>>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    config: Res<DropGhostConfig>,
+    card3d_config: Res<Card3DConfig>,
+    query_zone: Query<(Has<DeskZone>, Has<ZoneSlots>), With<Zone>>,
+    query_card: Query<Entity, With<Card>>,
+    query_parent: Query<&ChildOf>,
+) {
+    let zone_entity = drag_enter.target();
+    // 只有 DeskZone 和有格子的场地显示虚影
+    if !query_zone
+        .get(zone_entity)
+        .is_ok_and(|(desk, slots)| desk || slots)
+    {
+        return;
+    }
+    let Some(card) = find_card(drag_enter.dragged, &query_card, &query_parent) else {
+        return;
+    };
+    // 位置由 place_drop_ghost 计算
+    commands.spawn((
+        Name::new(format!("drop ghost of {:?}", card)),
+        DropGhost {
+            zone: zone_entity,
+            card,
+            point: drag_enter.hit.position,
+        },
+        Mesh3d(meshes.add(Cuboid::new(
+            card3d_config.width,
+            card3d_config.height,
+            card3d_config.thick,
+        ))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: config.color,
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        })),
+        Transform::default(),
+        Visibility::Hidden,
+        Pickable::IGNORE,
+    ));
+}
+
+/// 指针在场地上移动时 记录新的位置
+fn move_drop_ghost(
+    drag_over: Trigger<Pointer<DragOver>>,
+    mut query_ghost: Query<&mut DropGhost>,
+    query_card: Query<Entity, With<Card>>,
+    query_parent: Query<&ChildOf>,
+) {
+    let Some(card) = find_card(drag_over.dragged, &query_card, &query_parent) else {
+        return;
+    };
+    for mut ghost in query_ghost.iter_mut() {
+        if ghost.zone == drag_over.target() && ghost.card == card {
+            ghost.point = drag_over.hit.position.or(ghost.point);
+        }
+    }
+}
+
+/// 虚影跟随拖拽移动 和放下时使用同样的排列或格子
+///
+/// 不能放置的场地不显示虚影
+#[allow(clippy::too_many_arguments)]
+fn place_drop_ghost(
+    mut query_ghost: Query<(&DropGhost, &mut Transform, &mut Visibility), Without<Card>>,
+    query_zone: Query<(&Zone, Option<&DeskZone>, Option<&CardState>)>,
+    query_slots: Query<&ZoneSlots>,
+    query_feedback: Query<&DropFeedback>,
+    query_pose: Query<CardPose, With<Card>>,
+    card3d_config: Res<Card3DConfig>,
+    layouts: Res<ZoneLayouts>,
+) {
+    for (ghost, mut transform, mut visibility) in query_ghost.iter_mut() {
+        let rejected = query_feedback
+            .get(ghost.zone)
+            .is_ok_and(|feedback| !feedback.is_accept());
+        let opt_end = query_zone
+            .get(ghost.zone)
+            .ok()
+            .filter(|_| !rejected)
+            .and_then(
+                |(zone, opt_desk_zone, opt_zone_state)| match opt_desk_zone {
+                    Some(desk_zone) => desk_ghost_transform(
+                        ghost.card,
+                        zone,
+                        desk_zone,
+                        opt_zone_state,
+                        &query_pose,
+                        &card3d_config,
+                        &layouts,
+                    ),
+                    None => {
+                        slot_ghost_transform(ghost, zone, &query_slots, &query_pose, &card3d_config)
+                    }
+                },
+            );
+        match opt_end {
+            Some(end) => {
+                transform.set_if_neq(end);
+                visibility.set_if_neq(Visibility::Inherited);
+            }
+            None => {
+                visibility.set_if_neq(Visibility::Hidden);
+            }
+        }
+    }
+}
+
+/// DeskZone 中卡片放下后的位置 和 change_desk_cards_transform 一致
+fn desk_ghost_transform(
+    card: Entity,
+    zone: &Zone,
+    desk_zone: &DeskZone,
+    opt_zone_state: Option<&CardState>,
+    query_pose: &Query<CardPose, With<Card>>,
+    card3d_config: &Card3DConfig,
+    layouts: &ZoneLayouts,
+) -> Option<Transform> {
+    // 已在场地中的卡片保留自己的姿态
+    let (opt_card_state, card_transform) = query_pose.get(card).ok()?;
+    // 放满的场地放不下新的卡片
+    if desk_zone.is_full() && !desk_zone.card_list.contains(&card) {
+        return None;
+    }
+    let (index, opt_state) = match desk_zone.card_list.iter().position(|x| *x == card) {
+        Some(index) => (index, opt_card_state.cloned()),
+        None => (desk_zone.card_list.len(), opt_zone_state.cloned()),
+    };
+    // 场地中的卡片加上拖拽的卡片 横置的卡片占用更宽的位置
+    let mut orientations: Vec<CardOrientation> = desk_zone
+        .card_list
+        .iter()
+        .map(|card_entity| {
+            let opt_card_state = query_pose
+                .get(*card_entity)
+                .ok()
+                .and_then(|(opt_card_state, _)| opt_card_state);
+            desk_card_orientation(opt_card_state, opt_zone_state)
+        })
+        .collect();
+    let orientation = desk_card_orientation(opt_state.as_ref(), None);
+    match orientations.get_mut(index) {
+        Some(existing) => *existing = orientation,
+        None => orientations.push(orientation),
+    }
+    let slot = desk_card_slot(
+        zone,
+        desk_zone,
+        index,
+        &orientations,
+        Some(card_transform),
+        card3d_config,
+        layouts,
+    );
+    Some(calculate_transform(slot, opt_state))
+}
+
+/// 有格子的场地中卡片将要占用的格子 和 deal_drop_card_on_zone 一致
+fn slot_ghost_transform(
+    ghost: &DropGhost,
+    zone: &Zone,
+    query_slots: &Query<&ZoneSlots>,
+    query_pose: &Query<CardPose, With<Card>>,
+    card3d_config: &Card3DConfig,
+) -> Option<Transform> {
+    let slots = query_slots.get(ghost.zone).ok()?;
+    let point = ghost.point.or_else(|| {
+        query_pose
+            .get(ghost.card)
+            .ok()
+            .map(|(_, transform)| transform.translation)
+    })?;
+    let index = choose_slots(
+        ghost.zone,
+        &[(ghost.card, point)],
+        zone,
+        query_slots,
+        card3d_config,
+    )?[0];
+    let mut end = slots.slot_transform(zone, index)?;
+    end.translation.z += card3d_config.thick;
+    Some(end)
+}
+
+fn despawn_drop_ghost_on_leave(
+    drag_leave: Trigger<Pointer<DragLeave>>,
+    mut commands: Commands,
+    query_ghost: Query<(Entity, &DropGhost)>,
+) {
+    for (ghost_entity, ghost) in query_ghost.iter() {
+        if ghost.zone == drag_leave.target() {
+            commands.entity(ghost_entity).try_despawn();
+        }
+    }
+}
+
+fn despawn_drop_ghost_on_end(
+    drag_end: Trigger<Pointer<DragEnd>>,
+    mut commands: Commands,
+    query_ghost: Query<(Entity, &DropGhost)>,
+) {
+    for (ghost_entity, ghost) in query_ghost.iter() {
+        if ghost.card == drag_end.target() {
+            commands.entity(ghost_entity).try_despawn();
+        }
+    }
+}
@@ -5,6 +5,39 @@ use bevy::prelude::{Entity, Event};
 pub struct CardOnZone {
     pub card: Entity,
     pub zone: Entity,
+    // 场地有 ZoneSlots 时卡片所在的格子
+    pub slot: Option<usize>,
+}
+
+// 多张选中的卡片一起进入到场地
+#[derive(Event, Clone, Debug)]
+pub struct CardsOnZone {
+    pub cards: Vec<Entity>,
+    pub zone: Entity,
+    // 场地有 ZoneSlots 时每张卡片所在的格子 与 cards 一一对应
+    pub slots: Option<Vec<usize>>,
+}
+
+// 卡片不符合场地的放置规则
+#[derive(Event, Clone, Debug)]
+pub struct CardDropRejected {
+    pub card: Entity,
+    pub zone: Entity,
+}
+
+// 场地拒绝了卡片 卡片会回到原来的位置
+#[derive(Event, Clone, Debug)]
+pub struct ZoneRejected {
+    pub zone: Entity,
+    pub card: Entity,
+    pub reason: ZoneRejectReason,
+}
+
+// 场地拒绝卡片的原因
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ZoneRejectReason {
+    // 场地已满
+    Full { capacity: usize },
 }
 
 // 卡片进入到卡片上
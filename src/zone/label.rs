@@ -0,0 +1,308 @@
+use crate::zone::Zone;
+use crate::zone::deck_zone::DeckZone;
+use crate::zone::desk_zone::DeskZone;
+use bevy::prelude::*;
+use bevy::render::camera::RenderTarget;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages};
+use bevy::render::view::RenderLayers;
+use bevy_tween::combinator::{AnimationBuilderExt, TransformTargetStateExt, sequence, tween};
+use bevy_tween::prelude::{EaseKind, IntoTarget};
+use bevy_tween::tween::AnimationTarget;
+use std::time::Duration;
+
+/// 标签贴图的像素大小 宽高比决定标签的宽度
+const LABEL_TEXTURE_SIZE: UVec2 = UVec2::new(512, 128);
+
+/// 场地的文字标签 例如 "Graveyard: {count}"
+///
+/// 标签是贴在场地旁边的一块平面 文字先渲染到贴图上
+///
+/// 每个标签都有一个渲染到贴图的相机 只在文字变化时渲染几帧 其余时间关闭
+///
+/// `{count}` 会替换为 [`DeskZone`] 或 [`DeckZone`] 中卡片的数量
+#[derive(Component, Clone, Debug)]
+pub struct ZoneLabel {
+    pub text: String,
+    /// 标签在场地的哪一边
+    pub anchor: ZoneLabelAnchor,
+    /// 离场地边缘的距离
+    pub offset: f32,
+    /// 标签平面的高度 宽度为高度的 4 倍
+    pub height: f32,
+    /// 贴图上文字的像素大小
+    pub font_size: f32,
+    /// 默认使用 bevy 自带的字体 需要开启 `bevy/default_font`
+    pub font: Handle<Font>,
+    pub color: Color,
+    /// 数量变化时弹跳一下
+    pub pop: bool,
+}
+
+impl ZoneLabel {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            anchor: ZoneLabelAnchor::default(),
+            offset: 0.1,
+            height: 0.75,
+            font_size: 72.0,
+            font: Handle::default(),
+            color: Color::WHITE,
+            pop: true,
+        }
+    }
+
+    pub fn with_anchor(mut self, anchor: ZoneLabelAnchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    pub fn with_font(mut self, font: Handle<Font>) -> Self {
+        self.font = font;
+        self
+    }
+
+    /// 标签平面的大小
+    pub fn size(&self) -> Vec2 {
+        let aspect = LABEL_TEXTURE_SIZE.x as f32 / LABEL_TEXTURE_SIZE.y as f32;
+        Vec2::new(self.height * aspect, self.height)
+    }
+}
+
+/// 标签在场地的位置
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ZoneLabelAnchor {
+    #[default]
+    Top,
+    Bottom,
+    Left,
+    Right,
+    Center,
+}
+
+/// 显示标签的平面
+#[derive(Component, Clone, Copy, Debug)]
+pub struct ZoneLabelText {
+    pub zone: Entity,
+    /// 渲染文字到贴图的相机
+    camera: Entity,
+    ui: Entity,
+    text: Entity,
+    /// 上一次显示的数量
+    count: Option<usize>,
+    /// 还需要渲染的帧数 为 0 时关闭相机
+    render_frames: u8,
+}
+
+/// 文字变化后相机保持渲染的帧数 等待文字排版完成
+const LABEL_RENDER_FRAMES: u8 = 2;
+
+pub struct ZoneLabelPlugin;
+
+impl Plugin for ZoneLabelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(despawn_zone_label)
+            .add_systems(Update, (spawn_zone_label, update_zone_label).chain());
+    }
+}
+
+fn spawn_zone_label(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+    query: Query<(Entity, &ZoneLabel), Added<ZoneLabel>>,
+) {
+    for (zone_entity, label) in query.iter() {
+        let mut image = Image::new_fill(
+            Extent3d {
+                width: LABEL_TEXTURE_SIZE.x,
+                height: LABEL_TEXTURE_SIZE.y,
+                ..default()
+            },
+            TextureDimension::D2,
+            &[0, 0, 0, 0],
+            TextureFormat::Bgra8UnormSrgb,
+            RenderAssetUsages::default(),
+        );
+        image.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING
+            | TextureUsages::COPY_DST
+            | TextureUsages::RENDER_ATTACHMENT;
+        let image = images.add(image);
+
+        // 只渲染标签的 UI 不渲染场景中的任何东西
+        let camera = commands
+            .spawn((
+                Name::new(format!("zone label camera of {:?}", zone_entity)),
+                Camera2d,
+                Camera {
+                    order: -1,
+                    target: RenderTarget::Image(image.clone().into()),
+                    clear_color: ClearColorConfig::Custom(Color::NONE),
+                    ..default()
+                },
+                RenderLayers::none(),
+            ))
+            .id();
+        let mut text = Entity::PLACEHOLDER;
+        let ui = commands
+            .spawn((
+                Name::new(format!("zone label ui of {:?}", zone_entity)),
+                Node {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                UiTargetCamera(camera),
+            ))
+            .with_children(|parent| {
+                text = parent
+                    .spawn((
+                        Text::new(""),
+                        TextFont {
+                            font: label.font.clone(),
+                            font_size: label.font_size,
+                            ..default()
+                        },
+                        TextColor(label.color),
+                    ))
+                    .id();
+            })
+            .id();
+
+        commands.spawn((
+            Name::new(format!("zone label of {:?}", zone_entity)),
+            ZoneLabelText {
+                zone: zone_entity,
+                camera,
+                ui,
+                text,
+                count: None,
+                render_frames: LABEL_RENDER_FRAMES,
+            },
+            Mesh3d(meshes.add(Rectangle::from_size(label.size()))),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color_texture: Some(image),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..default()
+            })),
+            Transform::default(),
+            Visibility::Hidden,
+            AnimationTarget,
+            Pickable::IGNORE,
+        ));
+    }
+}
+
+/// 更新标签的文字 并让标签跟随场地
+#[allow(clippy::too_many_arguments)]
+fn update_zone_label(
+    mut commands: Commands,
+    mut query_label: Query<(Entity, &mut ZoneLabelText, &mut Transform, &mut Visibility)>,
+    mut query_text: Query<(&mut Text, &mut TextFont, &mut TextColor)>,
+    mut query_camera: Query<&mut Camera>,
+    query_zone: Query<(&Zone, Ref<ZoneLabel>)>,
+    fonts: Res<Assets<Font>>,
+    query_desk: Query<&DeskZone>,
+    query_deck: Query<&DeckZone>,
+) {
+    for (label_entity, mut label_text, mut transform, mut visibility) in query_label.iter_mut() {
+        let Ok((zone, label)) = query_zone.get(label_text.zone) else {
+            continue;
+        };
+        let opt_count = query_desk
+            .get(label_text.zone)
+            .map(|desk| desk.card_list.len())
+            .or(query_deck
+                .get(label_text.zone)
+                .map(|deck| deck.card_list.len()))
+            .ok();
+        let value = match opt_count {
+            Some(count) => label.text.replace("{count}", &count.to_string()),
+            None => label.text.clone(),
+        };
+        if let Ok((mut text, mut font, mut color)) = query_text.get_mut(label_text.text) {
+            if label.is_changed() {
+                font.font = label.font.clone();
+                font.font_size = label.font_size;
+                color.0 = label.color;
+                label_text.render_frames = LABEL_RENDER_FRAMES;
+            }
+            if text.set_if_neq(Text(value)) {
+                label_text.render_frames = LABEL_RENDER_FRAMES;
+            }
+        }
+        // 字体加载完成前一直渲染
+        if !fonts.contains(&label.font) {
+            label_text.render_frames = LABEL_RENDER_FRAMES;
+        }
+        let active = label_text.render_frames > 0;
+        if let Ok(mut camera) = query_camera.get_mut(label_text.camera)
+            && camera.is_active != active
+        {
+            camera.is_active = active;
+        }
+        label_text.render_frames = label_text.render_frames.saturating_sub(1);
+        if opt_count != label_text.count {
+            if label.pop && label_text.count.is_some() {
+                play_label_pop_animation(label_entity, &transform, &mut commands);
+            }
+            label_text.count = opt_count;
+        }
+
+        let half = (zone.size + label.size()) / 2.0 + Vec2::splat(label.offset);
+        let local = match label.anchor {
+            ZoneLabelAnchor::Top => Vec3::new(0.0, half.y, 0.0),
+            ZoneLabelAnchor::Bottom => Vec3::new(0.0, -half.y, 0.0),
+            ZoneLabelAnchor::Left => Vec3::new(-half.x, 0.0, 0.0),
+            ZoneLabelAnchor::Right => Vec3::new(half.x, 0.0, 0.0),
+            ZoneLabelAnchor::Center => Vec3::ZERO,
+        };
+        // 稍微高出场地 避免和场地重叠闪烁
+        let translation = zone.center.transform_point(local + Vec3::Z * 0.01);
+        if transform.translation != translation || transform.rotation != zone.center.rotation {
+            transform.translation = translation;
+            transform.rotation = zone.center.rotation;
+        }
+        visibility.set_if_neq(Visibility::Inherited);
+    }
+}
+
+/// 标签放大后弹回
+fn play_label_pop_animation(label_entity: Entity, transform: &Transform, commands: &mut Commands) {
+    let target = label_entity.into_target();
+    let mut start = target.transform_state(transform.with_scale(Vec3::ONE));
+    commands
+        .spawn(Name::new(format!("zone label pop {:?}", label_entity)))
+        .animation()
+        .insert(sequence((
+            tween(
+                Duration::from_secs_f32(0.1),
+                EaseKind::QuadraticOut,
+                start.scale_to(Vec3::splat(1.4)),
+            ),
+            tween(
+                Duration::from_secs_f32(0.2),
+                EaseKind::BounceOut,
+                start.scale_to(Vec3::ONE),
+            ),
+        )));
+}
+
+fn despawn_zone_label(
+    trigger: Trigger<OnRemove, ZoneLabel>,
+    mut commands: Commands,
+    query_label: Query<(Entity, &ZoneLabelText)>,
+) {
+    for (label_entity, label_text) in query_label.iter() {
+        if label_text.zone == trigger.target() {
+            commands.entity(label_text.camera).try_despawn();
+            commands.entity(label_text.ui).try_despawn();
+            commands.entity(label_entity).try_despawn();
+        }
+    }
+}
@@ -0,0 +1,382 @@
+use crate::card::card_state::CardOrientation;
+use crate::card3d::Card3DConfig;
+use crate::zone::Zone;
+use crate::zone::desk_zone::DeskZone;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+/// 场地中卡片的排列方式
+#[derive(Clone, Debug, Default, PartialEq, Reflect, Deserialize)]
+pub enum ZoneLayout {
+    /// 堆叠在场地中心，有平铺容量时沿 y 轴平铺
+    #[default]
+    Stack,
+    /// 牌堆，每张卡片占用 `thickness` 的高度
+    Pile { thickness: f32 },
+    /// 网格，每行 `columns` 张，超出后换行
+    ///
+    /// `compress` 时超出场地的网格压缩间距，卡片相互重叠
+    Grid {
+        columns: usize,
+        spacing: Vec2,
+        #[serde(default)]
+        compress: bool,
+    },
+    /// 水平一行
+    ///
+    /// `compress` 时超出场地宽度的一行压缩间距，卡片相互重叠
+    Row {
+        spacing: f32,
+        #[serde(default = "default_compress")]
+        compress: bool,
+    },
+    /// 扇形展开，`angle` 为总角度(弧度)
+    Fan { angle: f32, radius: f32 },
+    /// 纵向错开的一列，例如墓地
+    Column { offset: f32 },
+    /// 保持放下时的位置
+    Free,
+    /// 通过 [`register_zone_layout`] 注册的排列方式
+    Custom(String),
+}
+
+/// 计算排列时需要的信息
+pub struct ZoneLayoutContext<'a> {
+    pub zone: &'a Zone,
+    pub desk_zone: &'a DeskZone,
+    pub card3d_config: &'a Card3DConfig,
+    /// 参与排列的卡片数量
+    pub count: usize,
+    /// 参与排列的每张卡片的朝向 横置的卡片宽和高互换
+    pub orientations: &'a [CardOrientation],
+}
+
+impl ZoneLayoutContext<'_> {
+    /// 第 index 张卡片在桌面上占用的大小
+    pub fn footprint(&self, index: usize) -> Vec2 {
+        let size = Vec2::new(self.card3d_config.width, self.card3d_config.height);
+        self.orientations
+            .get(index)
+            .map_or(size, |orientation| orientation.footprint(size))
+    }
+}
+
+/// 自定义的排列方式
+pub trait ZoneLayoutStrategy: Send + Sync + 'static {
+    /// 第 index 张卡片的位置(不含姿态)，`current` 为卡片当前的位置
+    fn slot(
+        &self,
+        context: &ZoneLayoutContext,
+        index: usize,
+        current: Option<&Transform>,
+    ) -> Transform;
+}
+
+/// 已注册的自定义排列方式
+#[derive(Resource, Default)]
+pub struct ZoneLayouts(HashMap<String, Box<dyn ZoneLayoutStrategy>>);
+
+/// 注册自定义的排列方式 之后可以通过 `ZoneLayout::Custom(name)` 使用
+pub fn register_zone_layout<L>(app: &mut App, name: impl Into<String>, layout: L)
+where
+    L: ZoneLayoutStrategy,
+{
+    app.init_resource::<ZoneLayouts>();
+    app.world_mut()
+        .resource_mut::<ZoneLayouts>()
+        .0
+        .insert(name.into(), Box::new(layout));
+}
+
+impl ZoneLayout {
+    /// 第 index 张卡片的位置(不含姿态)
+    pub fn slot(
+        &self,
+        context: &ZoneLayoutContext,
+        index: usize,
+        current: Option<&Transform>,
+        layouts: &ZoneLayouts,
+    ) -> Transform {
+        let zone = context.zone;
+        let config = context.card3d_config;
+        let count = context.count.max(1);
+        let centered = |i: usize, n: usize| i as f32 - (n - 1) as f32 / 2.0;
+        let level = (index + 1) as f32 * config.thick;
+        match self {
+            ZoneLayout::Stack => place(zone, tiling_offset(context, index), level),
+            ZoneLayout::Pile { thickness } => place(
+                zone,
+                Vec2::ZERO,
+                (index + 1) as f32 * thickness.max(config.thick),
+            ),
+            ZoneLayout::Grid {
+                columns,
+                spacing,
+                compress,
+            } => {
+                let columns = (*columns).max(1);
+                let rows = count.div_ceil(columns);
+                let used_columns = count.min(columns);
+                // 每格按最大的卡片计算 横置的卡片也放得下
+                let cell = (0..count)
+                    .map(|i| context.footprint(i))
+                    .fold(Vec2::ZERO, Vec2::max);
+                let mut step = cell + *spacing;
+                if *compress {
+                    step.x = fit(step.x, zone.size.x, cell.x, used_columns);
+                    step.y = fit(step.y, zone.size.y, cell.y, rows);
+                }
+                let offset = Vec2::new(
+                    centered(index % columns, used_columns) * step.x,
+                    -centered(index / columns, rows) * step.y,
+                );
+                // 压缩后同一行的卡片相互重叠 后面的卡片在上面
+                let level = if *compress { level } else { config.thick };
+                place(zone, offset, level)
+            }
+            ZoneLayout::Row { spacing, compress } => {
+                // 每张卡片中心到第一张卡片中心的距离 横置的卡片更宽
+                let widths: Vec<f32> = (0..count).map(|i| context.footprint(i).x).collect();
+                let mut centers = vec![0.0; count];
+                for i in 1..count {
+                    centers[i] = centers[i - 1] + (widths[i - 1] + widths[i]) / 2.0 + spacing;
+                }
+                let span = centers[count - 1];
+                let mut scale = 1.0;
+                if *compress && span > 0.0 {
+                    let room = (zone.size.x - (widths[0] + widths[count - 1]) / 2.0).max(0.0);
+                    scale = (room / span).min(1.0);
+                }
+                let x = centers.get(index).map_or(0.0, |center| center - span / 2.0) * scale;
+                place(zone, Vec2::new(x, 0.0), level)
+            }
+            ZoneLayout::Fan { angle, radius } => {
+                let step = if count > 1 {
+                    angle / (count - 1) as f32
+                } else {
+                    0.0
+                };
+                let theta = centered(index, count) * step;
+                let offset = Vec2::new(radius * theta.sin(), radius * (theta.cos() - 1.0));
+                let mut slot = place(zone, offset, level);
+                slot.rotate_local_z(-theta);
+                slot
+            }
+            ZoneLayout::Column { offset } => {
+                let top = (zone.size.y - context.footprint(index).y) / 2.0;
+                place(zone, Vec2::new(0.0, top - index as f32 * offset), level)
+            }
+            ZoneLayout::Free => {
+                let Some(current) = current else {
+                    return place(zone, Vec2::ZERO, level);
+                };
+                // 换算到场地内 并限制在场地范围中
+                let local = zone.center.rotation.inverse()
+                    * (current.translation - zone.center.translation);
+                let half = (zone.size / 2.0).max(Vec2::ZERO);
+                place(zone, local.truncate().clamp(-half, half), level)
+            }
+            ZoneLayout::Custom(name) => match layouts.0.get(name) {
+                Some(layout) => layout.slot(context, index, current),
+                None => {
+                    warn!("zone layout {} is not registered", name);
+                    ZoneLayout::Stack.slot(context, index, current, layouts)
+                }
+            },
+        }
+    }
+}
+
+/// 平铺时第 index 张卡片在场地中的偏移
+fn tiling_offset(context: &ZoneLayoutContext, index: usize) -> Vec2 {
+    // 进行平铺！
+    let Some(capacity) = context.desk_zone.opt_capacity else {
+        return Vec2::ZERO;
+    };
+    let height = context.zone.size.y;
+    let per = height / capacity.max(1) as f32;
+    Vec2::new(0.0, -height / 2.0 + per / 2.0 + index as f32 * per)
+}
+
+/// 放不下 n 张卡片时压缩间距 `length` 为场地的长度 `card` 为卡片的长度
+fn fit(step: f32, length: f32, card: f32, n: usize) -> f32 {
+    if n > 1 {
+        step.min((length - card).max(0.0) / (n - 1) as f32)
+    } else {
+        step
+    }
+}
+
+fn default_compress() -> bool {
+    true
+}
+
+/// 场地坐标系下的偏移换算为世界坐标
+fn place(zone: &Zone, offset: Vec2, level: f32) -> Transform {
+    let mut slot = zone.center;
+    slot.translation += zone.center.rotation * offset.extend(0.0);
+    slot.translation.z = level;
+    slot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UPRIGHT: CardOrientation = CardOrientation::Upright;
+    const SIDEWAYS: CardOrientation = CardOrientation::Sideways;
+
+    fn zone(size: Vec2) -> Zone {
+        Zone {
+            center: Transform::default(),
+            size,
+        }
+    }
+
+    /// 排列所有卡片 返回场地坐标系下的位置
+    fn layout(layout: ZoneLayout, zone: &Zone, orientations: &[CardOrientation]) -> Vec<Vec2> {
+        let desk_zone = DeskZone {
+            layout: layout.clone(),
+            ..default()
+        };
+        let card3d_config = Card3DConfig::default();
+        let context = ZoneLayoutContext {
+            zone,
+            desk_zone: &desk_zone,
+            card3d_config: &card3d_config,
+            count: orientations.len(),
+            orientations,
+        };
+        (0..orientations.len())
+            .map(|index| {
+                layout
+                    .slot(&context, index, None, &ZoneLayouts::default())
+                    .translation
+                    .truncate()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn row_leaves_room_for_sideways_cards() {
+        let config = Card3DConfig::default();
+        let row = ZoneLayout::Row {
+            spacing: 0.0,
+            compress: false,
+        };
+        let slots = layout(
+            row,
+            &zone(Vec2::new(100.0, 5.0)),
+            &[UPRIGHT, SIDEWAYS, UPRIGHT],
+        );
+        let gap = (config.width + config.height) / 2.0;
+        assert!((slots[1].x - slots[0].x - gap).abs() < 1e-5);
+        assert!((slots[2].x - slots[1].x - gap).abs() < 1e-5);
+        // 仍然以场地中心对称
+        assert!((slots[0].x + slots[2].x).abs() < 1e-5);
+    }
+
+    #[test]
+    fn grid_cells_fit_sideways_cards() {
+        let config = Card3DConfig::default();
+        let grid = ZoneLayout::Grid {
+            columns: 2,
+            spacing: Vec2::ZERO,
+            compress: false,
+        };
+        let slots = layout(
+            grid,
+            &zone(Vec2::new(100.0, 100.0)),
+            &[UPRIGHT, SIDEWAYS, UPRIGHT, UPRIGHT],
+        );
+        // 每格按横置卡片的宽度和竖直卡片的高度计算
+        assert!((slots[1].x - slots[0].x - config.height).abs() < 1e-5);
+        assert!((slots[0].y - slots[2].y - config.height).abs() < 1e-5);
+    }
+
+    #[test]
+    fn compressed_row_keeps_sideways_cards_inside_zone() {
+        let config = Card3DConfig::default();
+        let row = ZoneLayout::Row {
+            spacing: 0.5,
+            compress: true,
+        };
+        let zone = zone(Vec2::new(8.0, 5.0));
+        let slots = layout(row, &zone, &[SIDEWAYS; 5]);
+        let half = zone.size.x / 2.0;
+        assert!(slots[0].x - config.height / 2.0 >= -half - 1e-5);
+        assert!(slots[4].x + config.height / 2.0 <= half + 1e-5);
+    }
+
+    #[test]
+    fn compressed_row_spaces_cards_evenly_inside_zone() {
+        let config = Card3DConfig::default();
+        let zone = zone(Vec2::new(10.0, 5.0));
+        let row = |compress| ZoneLayout::Row {
+            spacing: 1.0,
+            compress,
+        };
+        let loose = layout(row(false), &zone, &[UPRIGHT; 6]);
+        let slots = layout(row(true), &zone, &[UPRIGHT; 6]);
+        // 不压缩时超出场地
+        assert!(loose[5].x + config.width / 2.0 > zone.size.x / 2.0);
+        // 压缩后两端的卡片贴着场地边缘 间距相同
+        assert!((slots[5].x + config.width / 2.0 - zone.size.x / 2.0).abs() < 1e-5);
+        assert!((slots[0].x + slots[5].x).abs() < 1e-5);
+        let step = slots[1].x - slots[0].x;
+        for pair in slots.windows(2) {
+            assert!((pair[1].x - pair[0].x - step).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn row_fits_without_compression() {
+        let config = Card3DConfig::default();
+        let row = ZoneLayout::Row {
+            spacing: 0.5,
+            compress: true,
+        };
+        let slots = layout(row, &zone(Vec2::new(100.0, 5.0)), &[UPRIGHT; 3]);
+        assert!((slots[1].x - slots[0].x - (config.width + 0.5)).abs() < 1e-5);
+        assert!(slots[1].x.abs() < 1e-5);
+    }
+
+    #[test]
+    fn grid_wraps_after_columns() {
+        let config = Card3DConfig::default();
+        let grid = ZoneLayout::Grid {
+            columns: 2,
+            spacing: Vec2::splat(0.5),
+            compress: false,
+        };
+        let slots = layout(grid, &zone(Vec2::new(100.0, 100.0)), &[UPRIGHT; 5]);
+        let step = Vec2::new(config.width, config.height) + 0.5;
+        // 第三张卡片换到下一行的第一列
+        assert!((slots[2].x - slots[0].x).abs() < 1e-5);
+        assert!((slots[0].y - slots[2].y - step.y).abs() < 1e-5);
+        assert!((slots[1].x - slots[0].x - step.x).abs() < 1e-5);
+        // 三行以中间一行为中心
+        assert!(slots[2].y.abs() < 1e-5);
+        assert!((slots[4].y + step.y).abs() < 1e-5);
+    }
+
+    #[test]
+    fn fan_spreads_symmetrically() {
+        let fan = ZoneLayout::Fan {
+            angle: 1.0,
+            radius: 10.0,
+        };
+        let zone = zone(Vec2::new(100.0, 100.0));
+        let slots = layout(fan.clone(), &zone, &[UPRIGHT; 3]);
+        // 中间的卡片在场地中心 两边的卡片对称并且低一些
+        assert!(slots[1].length() < 1e-5);
+        assert!((slots[0].x + slots[2].x).abs() < 1e-5);
+        assert!((slots[0].y - slots[2].y).abs() < 1e-5);
+        assert!(slots[0].y < 0.0);
+        assert!((slots[2].x - 10.0 * 0.5_f32.sin()).abs() < 1e-5);
+        // 只有一张卡片时放在中心
+        let single = layout(fan, &zone, &[UPRIGHT]);
+        assert!(single[0].length() < 1e-5);
+    }
+}
@@ -1,20 +1,58 @@
+pub mod browse;
+pub mod deck_zone;
 pub mod desk_zone;
+pub mod despawn_policy;
+pub mod drop_filter;
+pub mod drop_ghost;
 pub mod events;
+pub mod label;
+pub mod layout;
+pub mod shape;
+pub mod slots;
+pub mod visibility;
+pub mod zone_highlight;
 
-use crate::prelude::Card;
-use crate::zone::desk_zone::DeskZonePlugin;
-use crate::zone::events::CardOnZone;
+use crate::card::card_state::CardState;
+use crate::card3d::Card3DConfig;
+use crate::prelude::attach::{Attachments, HostLeave};
+use crate::prelude::{Card, CardLine, GroupDragOffset, HandCard, HandCardChanged, Moveable};
+use crate::tween::animation::play_card_going_back_to_trans_animation;
+use crate::zone::browse::ZoneBrowserPlugin;
+use crate::zone::deck_zone::{DeckCard, DeckZone, DeckZonePlugin};
+use crate::zone::desk_zone::{DeskCard, DeskZone, DeskZoneChangedEvent, DeskZonePlugin};
+use crate::zone::despawn_policy::ZoneDespawnPlugin;
+use crate::zone::drop_filter::{DropFeedback, DropFilterPlugin};
+use crate::zone::drop_ghost::DropGhostPlugin;
+use crate::zone::events::{
+    CardDropRejected, CardOnZone, CardsOnZone, ZoneRejectReason, ZoneRejected,
+};
+use crate::zone::label::ZoneLabelPlugin;
+use crate::zone::shape::{ZoneShape, ZoneShapePlugin};
+use crate::zone::slots::{PlannedSlot, ZoneSlots, ZoneSlotsPlugin, choose_slots, join_slot};
+use crate::zone::visibility::ZoneVisibilityPlugin;
+use crate::zone::zone_highlight::ZoneHighlightPlugin;
 use bevy::app::App;
 use bevy::asset::Handle;
 use bevy::pbr::StandardMaterial;
 use bevy::prelude::*;
-use bevy_mod_outline::{GenerateOutlineNormalsSettings, OutlineMeshExt};
 
 pub struct ZonePlugin;
 
 impl Plugin for ZonePlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(DeskZonePlugin);
+        app.add_plugins((
+            DeskZonePlugin,
+            DeckZonePlugin,
+            DropFilterPlugin,
+            DropGhostPlugin,
+            ZoneSlotsPlugin,
+            ZoneHighlightPlugin,
+            ZoneShapePlugin,
+            ZoneVisibilityPlugin,
+            ZoneLabelPlugin,
+            ZoneDespawnPlugin,
+            ZoneBrowserPlugin,
+        ));
     }
 }
 pub trait ZoneMaterialGetter {
@@ -36,6 +74,146 @@ pub struct Zone {
     pub size: Vec2,
 }
 
+/// 把卡片送到手牌(CardLine)、DeskZone、DeckZone 或者普通的 Zone 上
+///
+/// 卡片会先离开原来所在的手牌、场地或牌堆
+pub fn send_card_to(commands: &mut Commands, card: Entity, to: Entity) {
+    commands.queue(move |world: &mut World| {
+        // 宿主离开原来的地方 附着的卡片按宿主的 OnHostLeave 处理
+        let same_desk = world
+            .get::<DeskCard>(card)
+            .is_some_and(|desk_card| desk_card.belongs_to_desk == Some(to));
+        if !same_desk && world.get::<Attachments>(card).is_some() {
+            world.trigger(HostLeave { host: card });
+        }
+        // 离开其他场地的格子 进入有格子的场地时由放置的流程分配格子
+        for (zone_entity, mut slots) in world.query::<(Entity, &mut ZoneSlots)>().iter_mut(world) {
+            if zone_entity != to && slots.index_of(card).is_some() {
+                slots.release(card);
+            }
+        }
+        let Ok(mut card_entity) = world.get_entity_mut(card) else {
+            return;
+        };
+        let opt_line = card_entity
+            .take::<HandCard>()
+            .and_then(|hand_card| hand_card.belong_to_card_line);
+        let opt_desk = card_entity
+            .take::<DeskCard>()
+            .and_then(|desk_card| desk_card.belongs_to_desk);
+        card_entity.remove::<DeckCard>();
+        if let Some(card_line_entity) = opt_line {
+            world.send_event(HandCardChanged::Remove {
+                card_entity: card,
+                card_line_entity,
+            });
+        }
+        if let Some(desk) = opt_desk {
+            world.send_event(DeskZoneChangedEvent::Removed { desk, card });
+        }
+
+        let Ok(target) = world.get_entity(to) else {
+            return;
+        };
+        if target.contains::<CardLine>() {
+            // 手牌正面朝上
+            world.entity_mut(card).remove::<CardState>().insert((
+                HandCard {
+                    belong_to_card_line: Some(to),
+                },
+                Moveable,
+            ));
+        } else if target.contains::<DeskZone>() {
+            world.entity_mut(card).insert(DeskCard {
+                belongs_to_desk: Some(to),
+            });
+        } else if target.contains::<DeckZone>() {
+            world.entity_mut(card).insert(DeckCard {
+                belongs_to_deck: Some(to),
+            });
+        } else if let Some(zone) = target.get::<Zone>().copied() {
+            let card3d_config = *world.resource::<Card3DConfig>();
+            let mut card_entity = world.entity_mut(card);
+            card_entity.remove::<CardState>();
+            let (Some(transform), Some(name)) = (
+                card_entity.get::<Transform>().copied(),
+                card_entity.get::<Name>().cloned(),
+            ) else {
+                return;
+            };
+            // 有格子的场地 占用放下时选好的格子
+            let planned = card_entity
+                .take::<PlannedSlot>()
+                .filter(|planned| planned.zone == to)
+                .map(|planned| planned.index);
+            let opt_origin = world.get_mut::<ZoneSlots>(to).and_then(|mut slots| {
+                join_slot(
+                    &mut slots,
+                    &zone,
+                    card,
+                    planned,
+                    transform.translation,
+                    &card3d_config,
+                )
+            });
+            let Some(mut card_component) = world.get_mut::<Card>(card) else {
+                return;
+            };
+            card_component.origin = opt_origin.unwrap_or_else(|| {
+                let mut origin = zone.center;
+                origin.translation.z = card3d_config.thick;
+                origin
+            });
+            let back_to = card_component.origin;
+            let mut commands = world.commands();
+            play_card_going_back_to_trans_animation(
+                card,
+                back_to,
+                &transform,
+                &name,
+                &mut commands,
+            );
+        }
+    });
+}
+
+/// 被拖拽的卡片和跟随它一起拖拽的卡片
+pub(crate) fn drag_group(
+    leader: Entity,
+    query_group: &Query<(Entity, &GroupDragOffset)>,
+) -> Vec<Entity> {
+    let followers = query_group
+        .iter()
+        .filter(|(_, group)| group.leader == leader)
+        .map(|(entity, _)| entity);
+    std::iter::once(leader).chain(followers).collect()
+}
+
+/// 场地独占的材质
+#[derive(Component, Clone, Copy, Debug)]
+pub(crate) struct OwnZoneMaterial;
+
+/// 修改场地材质前先复制一份
+///
+/// [`ZoneMaterialGetter::get_mal`] 可能返回共用的材质 直接修改会影响其他场地
+pub(crate) fn own_zone_material<'a>(
+    commands: &mut Commands,
+    zone_entity: Entity,
+    handle: &Handle<StandardMaterial>,
+    owned: bool,
+    materials: &'a mut Assets<StandardMaterial>,
+) -> Option<&'a mut StandardMaterial> {
+    if owned {
+        return materials.get_mut(handle);
+    }
+    let material = materials.get(handle)?.clone();
+    let handle = materials.add(material);
+    commands
+        .entity(zone_entity)
+        .insert((MeshMaterial3d(handle.clone()), OwnZoneMaterial));
+    materials.get_mut(&handle)
+}
+
 /// 绑定场地渲染的类
 pub fn bind_zone_render<T>(app: &mut App)
 where
@@ -49,43 +227,128 @@ fn spawn_zone<T>(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     asset_server: Res<AssetServer>,
-    query: Query<(Entity, &Zone, &T), Added<Zone>>,
+    query: Query<(Entity, &Zone, &T, Option<&ZoneShape>), Added<Zone>>,
 ) where
     T: Component + Clone + ZoneMaterialGetter,
 {
-    for (zone_entity, &zone, t) in query.iter() {
-        let mut mesh = Cuboid::new(zone.size.x, zone.size.y, 0.00001)
-            .mesh()
-            .build();
-        mesh.generate_outline_normals(&GenerateOutlineNormalsSettings::default())
-            .unwrap();
+    for (zone_entity, &zone, t, opt_shape) in query.iter() {
+        let mesh = opt_shape
+            .cloned()
+            .unwrap_or_default()
+            .mesh(zone.size, &mut meshes);
         commands
             .entity(zone_entity)
             .insert((
                 zone.center.clone(),
-                Mesh3d(meshes.add(mesh.clone())),
+                Mesh3d(mesh),
                 MeshMaterial3d(t.get_mal(&mut materials, &asset_server)),
             ))
             .observe(deal_drop_card_on_zone);
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn deal_drop_card_on_zone(
     drag_drop: Trigger<Pointer<DragDrop>>,
-    query_card: Query<Entity, With<Card>>,
-    query_zone: Query<Entity, (With<Zone>, Without<Card>)>,
+    query_entity: Query<Entity, With<Card>>,
+    query_zone: Query<(&Zone, Option<&DropFeedback>), Without<Card>>,
+    query_group: Query<(Entity, &GroupDragOffset)>,
+    query_transform: Query<&Transform, With<Card>>,
+    query_slots: Query<&ZoneSlots>,
+    query_desk_zone: Query<&DeskZone>,
+    card3d_config: Res<Card3DConfig>,
     query: Query<&ChildOf>,
     mut commands: Commands,
 ) {
-    info!("Drag drop: {:?}", drag_drop);
-    if let Ok(zone_entity) = query_zone.get(drag_drop.target) {
-        if let Ok(parent) = query.get(drag_drop.dropped) {
-            if let Ok(card_entity) = query_card.get(parent.parent()) {
-                commands.trigger(CardOnZone {
-                    card: card_entity,
-                    zone: zone_entity,
-                });
-            }
+    debug!("Drag drop: {:?}", drag_drop);
+    let zone_entity = drag_drop.target;
+    let Ok((zone, opt_feedback)) = query_zone.get(zone_entity) else {
+        return;
+    };
+    let Some(card_entity) = query
+        .get(drag_drop.dropped)
+        .ok()
+        .and_then(|parent| query_entity.get(parent.parent()).ok())
+    else {
+        return;
+    };
+    // 不符合放置规则的卡片 会在拖拽结束时自动回到原位
+    if opt_feedback == Some(&DropFeedback::Reject { card: card_entity }) {
+        commands.trigger(CardDropRejected {
+            card: card_entity,
+            zone: zone_entity,
+        });
+        return;
+    }
+    // 一起拖拽的卡片只触发一次
+    let cards = drag_group(card_entity, &query_group);
+    // 放不下时卡片还在原来的位置 松开后自己回去
+    if let Ok(desk_zone) = query_desk_zone.get(zone_entity)
+        && !desk_zone.has_room_for(&cards)
+    {
+        commands.trigger(CardDropRejected {
+            card: card_entity,
+            zone: zone_entity,
+        });
+        commands.trigger(ZoneRejected {
+            zone: zone_entity,
+            card: card_entity,
+            reason: ZoneRejectReason::Full {
+                capacity: desk_zone.opt_capacity.unwrap_or_default(),
+            },
+        });
+        return;
+    }
+    // 有格子的场地 吸附到格子上
+    let mut opt_slots = None;
+    if query_slots.contains(zone_entity) {
+        let drop_point = drag_drop.hit.position.or_else(|| {
+            query_transform
+                .get(card_entity)
+                .ok()
+                .map(|transform| transform.translation)
+        });
+        let points: Vec<(Entity, Vec3)> = cards
+            .iter()
+            .map(|card| {
+                let offset = query_group
+                    .get(*card)
+                    .map_or(Vec3::ZERO, |(_, group)| group.offset);
+                (
+                    *card,
+                    drop_point.unwrap_or(zone.center.translation) + offset,
+                )
+            })
+            .collect();
+        // 只选好格子 卡片加入场地时才占用
+        let Some(slots) = choose_slots(zone_entity, &points, zone, &query_slots, &card3d_config)
+        else {
+            // 没有足够的空格子
+            commands.trigger(CardDropRejected {
+                card: card_entity,
+                zone: zone_entity,
+            });
+            return;
+        };
+        for (card, index) in cards.iter().zip(slots.iter()) {
+            commands.entity(*card).insert(PlannedSlot {
+                zone: zone_entity,
+                index: *index,
+            });
         }
+        opt_slots = Some(slots);
+    }
+    if cards.len() > 1 {
+        commands.trigger(CardsOnZone {
+            cards,
+            zone: zone_entity,
+            slots: opt_slots,
+        });
+        return;
     }
+    commands.trigger(CardOnZone {
+        card: card_entity,
+        zone: zone_entity,
+        slot: opt_slots.map(|slots| slots[0]),
+    });
 }
@@ -0,0 +1,178 @@
+use crate::zone::Zone;
+use bevy::asset::RenderAssetUsages;
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy_mod_outline::{ATTRIBUTE_OUTLINE_NORMAL, GenerateOutlineNormalsSettings, OutlineMeshExt};
+use serde::Deserialize;
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+/// 场地网格的厚度
+const ZONE_THICK: f32 = 0.00001;
+
+/// 场地的形状 没有时为矩形
+///
+/// 形状都限制在 `Zone.size` 的范围内
+#[derive(Component, Clone, Debug, Default, PartialEq, Deserialize)]
+pub enum ZoneShape {
+    #[default]
+    Rect,
+    /// 直径为 size 较短的一边
+    Circle,
+    /// 圆角矩形 和卡片的轮廓一致时 radius 使用 `Card3DConfig.radius`
+    RoundedRect { radius: f32 },
+    /// 自定义网格 放在场地中心 生成轮廓法线后用于高亮
+    #[serde(skip)]
+    Mesh(Handle<Mesh>),
+}
+
+impl ZoneShape {
+    /// 场地的网格
+    pub fn mesh(&self, size: Vec2, meshes: &mut Assets<Mesh>) -> Handle<Mesh> {
+        let mut mesh = match self {
+            ZoneShape::Rect => Cuboid::new(size.x, size.y, ZONE_THICK).mesh().build(),
+            ZoneShape::Circle | ZoneShape::RoundedRect { .. } => {
+                prism_mesh(&self.outline(size), size, ZONE_THICK)
+            }
+            ZoneShape::Mesh(handle) => {
+                prepare_outline_normals(meshes, handle);
+                return handle.clone();
+            }
+        };
+        mesh.generate_outline_normals(&GenerateOutlineNormalsSettings::default())
+            .unwrap();
+        meshes.add(mesh)
+    }
+
+    /// 逆时针的轮廓 自定义网格时为 size 的矩形
+    pub fn outline(&self, size: Vec2) -> Vec<Vec2> {
+        let half = size / 2.0;
+        match self {
+            ZoneShape::Rect | ZoneShape::Mesh(_) => vec![
+                Vec2::new(-half.x, -half.y),
+                Vec2::new(half.x, -half.y),
+                Vec2::new(half.x, half.y),
+                Vec2::new(-half.x, half.y),
+            ],
+            ZoneShape::Circle => {
+                let radius = half.min_element();
+                (0..64)
+                    .map(|i| Vec2::from_angle(TAU * i as f32 / 64.0) * radius)
+                    .collect()
+            }
+            ZoneShape::RoundedRect { radius } => {
+                let radius = radius.clamp(0.0, half.min_element());
+                let inner = half - Vec2::splat(radius);
+                // 从右下角开始 每个角 8 段
+                [
+                    Vec2::new(inner.x, -inner.y),
+                    Vec2::new(inner.x, inner.y),
+                    Vec2::new(-inner.x, inner.y),
+                    Vec2::new(-inner.x, -inner.y),
+                ]
+                .iter()
+                .enumerate()
+                .flat_map(|(corner, center)| {
+                    let start = -FRAC_PI_2 + FRAC_PI_2 * corner as f32;
+                    (0..=8).map(move |i| {
+                        *center + Vec2::from_angle(start + FRAC_PI_2 * i as f32 / 8.0) * radius
+                    })
+                })
+                .collect()
+            }
+        }
+    }
+}
+
+pub struct ZoneShapePlugin;
+
+impl Plugin for ZoneShapePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, prepare_custom_zone_mesh);
+    }
+}
+
+/// 自定义网格加载完成后生成轮廓法线
+fn prepare_custom_zone_mesh(
+    mut events: EventReader<AssetEvent<Mesh>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    query_zone: Query<&ZoneShape, With<Zone>>,
+) {
+    for event in events.read() {
+        let (AssetEvent::Added { id } | AssetEvent::LoadedWithDependencies { id }) = event else {
+            continue;
+        };
+        let opt_handle = query_zone.iter().find_map(|shape| match shape {
+            ZoneShape::Mesh(handle) if handle.id() == *id => Some(handle.clone()),
+            _ => None,
+        });
+        if let Some(handle) = opt_handle {
+            prepare_outline_normals(&mut meshes, &handle);
+        }
+    }
+}
+
+fn prepare_outline_normals(meshes: &mut Assets<Mesh>, handle: &Handle<Mesh>) {
+    let Some(mesh) = meshes.get(handle) else {
+        return;
+    };
+    if mesh.contains_attribute(ATTRIBUTE_OUTLINE_NORMAL) {
+        return;
+    }
+    let Some(mesh) = meshes.get_mut(handle) else {
+        return;
+    };
+    if let Err(err) = mesh.generate_outline_normals(&GenerateOutlineNormalsSettings::default()) {
+        warn!("zone mesh outline normals: {:?}", err);
+    }
+}
+
+/// 把凸的轮廓挤出成很薄的柱体
+fn prism_mesh(outline: &[Vec2], size: Vec2, thick: f32) -> Mesh {
+    let count = outline.len() as u32;
+    let half = thick / 2.0;
+    let uv = |point: Vec2| [point.x / size.x + 0.5, 0.5 - point.y / size.y];
+    let mut positions = vec![];
+    let mut normals = vec![];
+    let mut uvs = vec![];
+    let mut indices = vec![];
+    // 上下两面
+    for (z, normal) in [(half, Vec3::Z), (-half, Vec3::NEG_Z)] {
+        let base = positions.len() as u32;
+        positions.push([0.0, 0.0, z]);
+        normals.push(normal);
+        uvs.push([0.5, 0.5]);
+        for point in outline {
+            positions.push([point.x, point.y, z]);
+            normals.push(normal);
+            uvs.push(uv(*point));
+        }
+        for i in 0..count {
+            let (a, b) = (base + 1 + i, base + 1 + (i + 1) % count);
+            if z > 0.0 {
+                indices.extend([base, a, b]);
+            } else {
+                indices.extend([base, b, a]);
+            }
+        }
+    }
+    // 侧面
+    for i in 0..outline.len() {
+        let (p0, p1) = (outline[i], outline[(i + 1) % outline.len()]);
+        let normal = (p1 - p0).perp().normalize_or_zero() * -1.0;
+        let base = positions.len() as u32;
+        for (point, z) in [(p0, -half), (p1, -half), (p1, half), (p0, half)] {
+            positions.push([point.x, point.y, z]);
+            normals.push(normal.extend(0.0));
+            uvs.push(uv(point));
+        }
+        indices.extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+    .with_inserted_indices(Indices::U32(indices))
+}
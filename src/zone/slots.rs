@@ -0,0 +1,332 @@
+use crate::card3d::Card3DConfig;
+use crate::prelude::{Card, HandCard};
+use crate::zone::Zone;
+use crate::zone::deck_zone::DeckCard;
+use crate::zone::desk_zone::DeskCard;
+use bevy::prelude::*;
+
+/// 场地中的一个格子
+#[derive(Clone, Copy, Debug, Default, Reflect)]
+pub struct ZoneSlot {
+    /// 相对于场地中心的位置
+    pub transform: Transform,
+    /// 占用这个格子的卡片
+    pub occupant: Option<Entity>,
+}
+
+/// 场地中固定的格子 例如五个怪兽区
+///
+/// 放到场地上的卡片会吸附到指定的空格子，没有指定时吸附到离落点最近的空格子
+#[derive(Component, Clone, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct ZoneSlots {
+    pub slots: Vec<ZoneSlot>,
+}
+
+impl ZoneSlots {
+    pub fn new(transforms: impl IntoIterator<Item = Transform>) -> Self {
+        Self {
+            slots: transforms
+                .into_iter()
+                .map(|transform| ZoneSlot {
+                    transform,
+                    occupant: None,
+                })
+                .collect(),
+        }
+    }
+
+    /// 沿 x 轴等距排成一行
+    pub fn row(count: usize, spacing: f32) -> Self {
+        Self::new((0..count).map(|i| {
+            Transform::from_xyz((i as f32 - (count as f32 - 1.0) / 2.0) * spacing, 0.0, 0.0)
+        }))
+    }
+
+    /// 格子在世界坐标下的位置
+    pub fn slot_transform(&self, zone: &Zone, index: usize) -> Option<Transform> {
+        self.slots
+            .get(index)
+            .map(|slot| zone.center.mul_transform(slot.transform))
+    }
+
+    /// 卡片所在的格子
+    pub fn index_of(&self, card: Entity) -> Option<usize> {
+        self.slots
+            .iter()
+            .position(|slot| slot.occupant == Some(card))
+    }
+
+    /// 离 point 最近的空格子
+    pub fn nearest_empty(&self, zone: &Zone, point: Vec3) -> Option<usize> {
+        (0..self.slots.len())
+            .filter(|index| self.slots[*index].occupant.is_none())
+            .filter_map(|index| {
+                self.slot_transform(zone, index).map(|slot| {
+                    (
+                        index,
+                        slot.translation.truncate().distance(point.truncate()),
+                    )
+                })
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(index, _)| index)
+    }
+
+    /// point 落在哪个格子上 格子的大小和卡片一致
+    pub fn slot_at(&self, zone: &Zone, point: Vec3, card_size: Vec2) -> Option<usize> {
+        (0..self.slots.len()).find(|index| {
+            self.slot_transform(zone, *index).is_some_and(|slot| {
+                let local = slot.rotation.inverse() * (point - slot.translation);
+                local.x.abs() <= card_size.x / 2.0 && local.y.abs() <= card_size.y / 2.0
+            })
+        })
+    }
+
+    /// 放置卡片时选择的格子：落在空格子上时使用这个格子 否则使用最近的空格子
+    pub fn choose(&self, zone: &Zone, point: Vec3, card_size: Vec2) -> Option<usize> {
+        self.slot_at(zone, point, card_size)
+            .filter(|index| self.slots[*index].occupant.is_none())
+            .or_else(|| self.nearest_empty(zone, point))
+    }
+
+    /// 占用格子
+    pub fn occupy(&mut self, index: usize, card: Entity) {
+        self.release(card);
+        if let Some(slot) = self.slots.get_mut(index) {
+            slot.occupant = Some(card);
+        }
+    }
+
+    /// 卡片离开格子
+    pub fn release(&mut self, card: Entity) -> Option<usize> {
+        let index = self.index_of(card)?;
+        self.slots[index].occupant = None;
+        Some(index)
+    }
+}
+
+/// 放下卡片时选好的格子 卡片真正加入场地时才占用
+#[derive(Component, Clone, Copy, Debug)]
+pub(crate) struct PlannedSlot {
+    pub zone: Entity,
+    pub index: usize,
+}
+
+pub struct ZoneSlotsPlugin;
+
+impl Plugin for ZoneSlotsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ZoneSlots>()
+            .add_observer(release_removed_card)
+            .add_observer(release_on_hand_card)
+            .add_observer(release_on_deck_card)
+            .add_observer(release_on_desk_card);
+    }
+}
+
+/// 卡片被删除时空出格子
+fn release_removed_card(
+    trigger: Trigger<OnRemove, Card>,
+    mut query_slots: Query<(Entity, &mut ZoneSlots)>,
+) {
+    release_slots_except(trigger.target(), None, &mut query_slots);
+}
+
+/// 卡片离开除 keep 以外所有场地的格子
+fn release_slots_except(
+    card: Entity,
+    keep: Option<Entity>,
+    query_slots: &mut Query<(Entity, &mut ZoneSlots)>,
+) {
+    for (zone_entity, mut slots) in query_slots.iter_mut() {
+        if Some(zone_entity) != keep && slots.index_of(card).is_some() {
+            slots.release(card);
+        }
+    }
+}
+
+/// 卡片回到手牌时空出格子
+fn release_on_hand_card(
+    trigger: Trigger<OnInsert, HandCard>,
+    mut commands: Commands,
+    mut query_slots: Query<(Entity, &mut ZoneSlots)>,
+) {
+    release_slots_except(trigger.target(), None, &mut query_slots);
+    commands.entity(trigger.target()).remove::<PlannedSlot>();
+}
+
+/// 卡片回到牌堆时空出格子
+fn release_on_deck_card(
+    trigger: Trigger<OnInsert, DeckCard>,
+    mut commands: Commands,
+    mut query_slots: Query<(Entity, &mut ZoneSlots)>,
+) {
+    release_slots_except(trigger.target(), None, &mut query_slots);
+    commands.entity(trigger.target()).remove::<PlannedSlot>();
+}
+
+/// 卡片进入其他 DeskZone 时空出原来的格子 进入有格子的 DeskZone 时占用格子
+#[allow(clippy::too_many_arguments)]
+fn release_on_desk_card(
+    trigger: Trigger<OnInsert, DeskCard>,
+    mut commands: Commands,
+    query_desk_card: Query<&DeskCard>,
+    query_planned: Query<&PlannedSlot>,
+    mut query_slots: Query<(Entity, &mut ZoneSlots)>,
+    query_zone: Query<&Zone>,
+    mut query_card: Query<(&mut Card, &Transform)>,
+    card3d_config: Res<Card3DConfig>,
+) {
+    let card_entity = trigger.target();
+    let Ok(desk_card) = query_desk_card.get(card_entity) else {
+        return;
+    };
+    release_slots_except(card_entity, desk_card.belongs_to_desk, &mut query_slots);
+    let Some(desk) = desk_card.belongs_to_desk else {
+        return;
+    };
+    // 放下时选好的格子
+    let planned = query_planned
+        .get(card_entity)
+        .ok()
+        .filter(|planned| planned.zone == desk)
+        .map(|planned| planned.index);
+    commands.entity(card_entity).remove::<PlannedSlot>();
+    let (Ok((_, mut slots)), Ok(zone), Ok((mut card, transform))) = (
+        query_slots.get_mut(desk),
+        query_zone.get(desk),
+        query_card.get_mut(card_entity),
+    ) else {
+        return;
+    };
+    if let Some(origin) = join_slot(
+        &mut slots,
+        zone,
+        card_entity,
+        planned,
+        transform.translation,
+        &card3d_config,
+    ) {
+        card.origin = origin;
+    }
+}
+
+/// 为放到场地上的卡片选择格子 不占用格子
+///
+/// 卡片原来所在的格子视为空出，没有足够的空格子时返回 None
+pub(crate) fn choose_slots(
+    zone_entity: Entity,
+    cards: &[(Entity, Vec3)],
+    zone: &Zone,
+    query_slots: &Query<&ZoneSlots>,
+    card3d_config: &Card3DConfig,
+) -> Option<Vec<usize>> {
+    let card_size = Vec2::new(card3d_config.width, card3d_config.height);
+    // 在副本上依次选好格子 一起放下的卡片不会选到同一个格子
+    let mut slots = query_slots.get(zone_entity).ok()?.clone();
+    for (card, _) in cards {
+        slots.release(*card);
+    }
+    let mut chosen = Vec::new();
+    for (card, point) in cards {
+        let index = slots.choose(zone, *point, card_size)?;
+        slots.occupy(index, *card);
+        chosen.push(index);
+    }
+    Some(chosen)
+}
+
+/// 卡片加入有格子的场地时占用格子 返回卡片的原位
+///
+/// 优先使用 `slot`，它已经被其他卡片占用时使用离卡片最近的空格子
+pub(crate) fn join_slot(
+    slots: &mut ZoneSlots,
+    zone: &Zone,
+    card: Entity,
+    slot: Option<usize>,
+    point: Vec3,
+    card3d_config: &Card3DConfig,
+) -> Option<Transform> {
+    let card_size = Vec2::new(card3d_config.width, card3d_config.height);
+    let index = slot
+        .filter(|index| {
+            slots
+                .slots
+                .get(*index)
+                .is_some_and(|slot| slot.occupant.is_none() || slot.occupant == Some(card))
+        })
+        .or_else(|| slots.index_of(card))
+        .or_else(|| slots.choose(zone, point, card_size))?;
+    slots.occupy(index, card);
+    let mut origin = slots.slot_transform(zone, index)?;
+    origin.translation.z += card3d_config.thick;
+    Some(origin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARD: Vec2 = Vec2::new(2.0, 3.0);
+
+    fn zone() -> Zone {
+        Zone {
+            center: Transform::from_xyz(10.0, 0.0, 0.0),
+            size: Vec2::new(20.0, 5.0),
+        }
+    }
+
+    /// x 为 6 8 10 12 14 的五个格子 中间的格子被占用
+    fn slots() -> ZoneSlots {
+        let mut slots = ZoneSlots::row(5, 2.0);
+        slots.occupy(2, Entity::from_raw(1));
+        slots
+    }
+
+    #[test]
+    fn choose_uses_empty_slot_under_point() {
+        let slots = slots();
+        assert_eq!(
+            slots.choose(&zone(), Vec3::new(12.3, 0.5, 0.0), CARD),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn choose_skips_occupied_slot_under_point() {
+        let slots = slots();
+        // 落在被占用的格子上 使用最近的空格子
+        let chosen = slots.choose(&zone(), Vec3::new(10.4, 0.0, 0.0), CARD);
+        assert_eq!(chosen, Some(3));
+        let chosen = slots.choose(&zone(), Vec3::new(9.6, 0.0, 0.0), CARD);
+        assert_eq!(chosen, Some(1));
+    }
+
+    #[test]
+    fn nearest_empty_ignores_occupied_slots() {
+        let mut slots = slots();
+        slots.occupy(3, Entity::from_raw(2));
+        slots.occupy(4, Entity::from_raw(3));
+        assert_eq!(
+            slots.nearest_empty(&zone(), Vec3::new(14.0, 0.0, 0.0)),
+            Some(1)
+        );
+        for index in 0..2 {
+            slots.occupy(index, Entity::from_raw(10 + index as u32));
+        }
+        assert_eq!(slots.nearest_empty(&zone(), Vec3::ZERO), None);
+        assert_eq!(slots.choose(&zone(), Vec3::ZERO, CARD), None);
+    }
+
+    #[test]
+    fn occupy_moves_card_between_slots() {
+        let mut slots = slots();
+        let card = Entity::from_raw(1);
+        slots.occupy(0, card);
+        assert_eq!(slots.index_of(card), Some(0));
+        assert!(slots.slots[2].occupant.is_none());
+        assert_eq!(slots.release(card), Some(0));
+        assert_eq!(slots.release(card), None);
+    }
+}
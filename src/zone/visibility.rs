@@ -0,0 +1,129 @@
+use crate::card::card_material::CardMaterial;
+use crate::prelude::{Card, CardBack, CardFace, CardImages, HandCard};
+use crate::zone::deck_zone::DeckCard;
+use crate::zone::desk_zone::DeskCard;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+/// 玩家
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect, Deserialize)]
+pub struct PlayerId(pub u32);
+
+/// 场地或手牌的主人
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct ZoneOwner(pub PlayerId);
+
+/// 谁可以看到场地或手牌中卡片的正面
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect, Deserialize)]
+#[reflect(Component)]
+pub enum ZoneVisibility {
+    /// 所有人都按卡片的姿态看到正面或背面
+    #[default]
+    Public,
+    /// 主人总是看到正面，其他人总是看到背面
+    OwnerOnly,
+    /// 所有人都只能看到背面
+    Hidden,
+}
+
+impl ZoneVisibility {
+    /// 场地中的卡片对 viewer 显示的样子
+    pub fn view(&self, owner: Option<PlayerId>, viewer: Option<PlayerId>) -> CardView {
+        match self {
+            ZoneVisibility::Public => CardView::Natural,
+            ZoneVisibility::OwnerOnly if owner.is_some() && owner == viewer => CardView::Face,
+            ZoneVisibility::OwnerOnly | ZoneVisibility::Hidden => CardView::Back,
+        }
+    }
+}
+
+/// 当前观看的玩家 None 时为旁观者
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LocalViewer(pub Option<PlayerId>);
+
+/// 卡片对当前玩家显示的样子 与卡片的姿态 CardState 无关
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CardView {
+    /// 正面显示正面图片，背面显示背面图片
+    #[default]
+    Natural,
+    /// 两面都显示正面图片
+    Face,
+    /// 两面都显示背面图片
+    Back,
+}
+
+pub struct ZoneVisibilityPlugin;
+
+impl Plugin for ZoneVisibilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LocalViewer>()
+            .register_type::<ZoneOwner>()
+            .register_type::<ZoneVisibility>()
+            .add_systems(PostUpdate, update_card_view);
+    }
+}
+
+/// 按照卡片所在位置的规则和当前玩家计算卡片的样子
+#[allow(clippy::too_many_arguments)]
+fn update_card_view(
+    mut commands: Commands,
+    viewer: Res<LocalViewer>,
+    query_card: Query<(Entity, &CardImages, &Children), With<Card>>,
+    query_hand_card: Query<&HandCard>,
+    query_desk_card: Query<&DeskCard>,
+    query_deck_card: Query<&DeckCard>,
+    query_view: Query<&CardView>,
+    query_zone: Query<(&ZoneVisibility, Option<&ZoneOwner>)>,
+    query_face: Query<&MeshMaterial3d<CardMaterial>, With<CardFace>>,
+    query_back: Query<&MeshMaterial3d<StandardMaterial>, With<CardBack>>,
+    mut card_materials: ResMut<Assets<CardMaterial>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (card_entity, images, children) in query_card.iter() {
+        // 卡片所在的手牌、场地或牌堆
+        let opt_container = query_hand_card
+            .get(card_entity)
+            .ok()
+            .and_then(|hand_card| hand_card.belong_to_card_line)
+            .or(query_desk_card
+                .get(card_entity)
+                .ok()
+                .and_then(|desk_card| desk_card.belongs_to_desk))
+            .or(query_deck_card
+                .get(card_entity)
+                .ok()
+                .and_then(|deck_card| deck_card.belongs_to_deck));
+        let view = opt_container
+            .and_then(|zone| query_zone.get(zone).ok())
+            .map_or(CardView::Natural, |(visibility, opt_owner)| {
+                visibility.view(opt_owner.map(|owner| owner.0), viewer.0)
+            });
+        if query_view.get(card_entity).copied().unwrap_or_default() == view {
+            continue;
+        }
+        let (face_image, back_image) = match view {
+            CardView::Natural => (&images.face, &images.back),
+            CardView::Face => (&images.face, &images.face),
+            CardView::Back => (&images.back, &images.back),
+        };
+        for child in children.iter() {
+            if let Some(material) = query_face
+                .get(child)
+                .ok()
+                .and_then(|material| card_materials.get_mut(&material.0))
+            {
+                material.base_color_texture = face_image.clone();
+            }
+            if let Some(material) = query_back
+                .get(child)
+                .ok()
+                .and_then(|material| materials.get_mut(&material.0))
+            {
+                material.base_color_texture = Some(back_image.clone());
+            }
+        }
+        commands.entity(card_entity).insert(view);
+    }
+}
@@ -0,0 +1,253 @@
+use crate::prelude::Card;
+use crate::zone::drop_filter::{DropFeedback, DropFeedbackConfig, find_card};
+use crate::zone::shape::ZoneShape;
+use crate::zone::{OwnZoneMaterial, Zone, own_zone_material};
+use bevy::asset::RenderAssetUsages;
+use bevy::pbr::NotShadowCaster;
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+
+/// 场地高亮的样式
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+pub enum ZoneHighlightStyle {
+    /// 场地周围的边框
+    Border { width: f32 },
+    /// 向外渐隐的光晕
+    Glow { width: f32 },
+    /// 场地本身发出颜色
+    Tint { strength: f32 },
+}
+
+/// 场地的高亮
+///
+/// 边框和光晕是场地下方的网格，不会盖住场地上的卡片
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct ZoneHighlight {
+    pub color: Color,
+    pub style: ZoneHighlightStyle,
+}
+
+/// 拖拽卡片经过场地时的高亮
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct ZoneHoverConfig {
+    pub enabled: bool,
+    pub highlight: ZoneHighlight,
+}
+
+impl Default for ZoneHoverConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            highlight: ZoneHighlight {
+                color: Color::srgba(1.0, 1.0, 1.0, 0.8),
+                style: ZoneHighlightStyle::Glow { width: 0.4 },
+            },
+        }
+    }
+}
+
+/// 单独设置某个场地拖拽经过时的高亮 None 时不高亮
+#[derive(Component, Clone, Copy, Debug)]
+pub struct ZoneHoverStyle(pub Option<ZoneHighlight>);
+
+/// 正在被拖拽经过的场地
+#[derive(Component, Clone, Copy, Debug)]
+pub struct ZoneHovered {
+    pub card: Entity,
+}
+
+/// 已经显示的高亮
+#[derive(Component, Clone, Copy, Debug)]
+struct AppliedZoneHighlight {
+    highlight: ZoneHighlight,
+    /// 边框或光晕的网格
+    frame: Option<Entity>,
+    /// Tint 高亮前的自发光颜色
+    emissive: Option<LinearRgba>,
+}
+
+pub struct ZoneHighlightPlugin;
+
+impl Plugin for ZoneHighlightPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ZoneHoverConfig>()
+            .register_type::<ZoneHighlight>()
+            .add_observer(on_drag_enter_zone)
+            .add_observer(on_drag_leave_zone)
+            .add_systems(Update, apply_zone_highlight);
+    }
+}
+
+fn on_drag_enter_zone(
+    drag_enter: Trigger<Pointer<DragEnter>>,
+    mut commands: Commands,
+    query_zone: Query<(), With<Zone>>,
+    query_card: Query<Entity, With<Card>>,
+    query_parent: Query<&ChildOf>,
+) {
+    if !query_zone.contains(drag_enter.target()) {
+        return;
+    }
+    if let Some(card) = find_card(drag_enter.dragged, &query_card, &query_parent) {
+        commands
+            .entity(drag_enter.target())
+            .insert(ZoneHovered { card });
+    }
+}
+
+fn on_drag_leave_zone(
+    drag_leave: Trigger<Pointer<DragLeave>>,
+    mut commands: Commands,
+    query_zone: Query<(), With<ZoneHovered>>,
+) {
+    if query_zone.contains(drag_leave.target()) {
+        commands.entity(drag_leave.target()).remove::<ZoneHovered>();
+    }
+}
+
+/// 根据拖拽状态、放置规则和用户的高亮决定场地当前的高亮
+#[allow(clippy::too_many_arguments)]
+fn apply_zone_highlight(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    hover_config: Res<ZoneHoverConfig>,
+    feedback_config: Res<DropFeedbackConfig>,
+    query_zone: Query<(Entity, &Zone, &MeshMaterial3d<StandardMaterial>)>,
+    query_shape: Query<&ZoneShape>,
+    query_hovered: Query<&ZoneHovered>,
+    query_feedback: Query<&DropFeedback>,
+    query_hover_style: Query<&ZoneHoverStyle>,
+    query_highlight: Query<&ZoneHighlight>,
+    query_applied: Query<&AppliedZoneHighlight>,
+    query_owned: Query<(), With<OwnZoneMaterial>>,
+) {
+    for (zone_entity, zone, material) in query_zone.iter() {
+        let hover = query_hover_style.get(zone_entity).map_or(
+            hover_config.enabled.then_some(hover_config.highlight),
+            |style| style.0,
+        );
+        let wanted = match (
+            query_feedback.get(zone_entity).ok(),
+            query_hovered.contains(zone_entity),
+        ) {
+            // 可以放下时使用放置规则的颜色
+            (Some(DropFeedback::Accept { .. }), _) => Some(ZoneHighlight {
+                color: feedback_config.accept_color,
+                ..hover.unwrap_or(hover_config.highlight)
+            }),
+            // 不能放下时场地会变暗
+            (Some(DropFeedback::Reject { .. }), _) => None,
+            (None, true) => hover,
+            (None, false) => None,
+        }
+        .or(query_highlight.get(zone_entity).ok().copied());
+
+        let opt_applied = query_applied.get(zone_entity).ok();
+        if opt_applied.map(|applied| applied.highlight) == wanted {
+            continue;
+        }
+        // 先清除旧的高亮
+        let mut restored = None;
+        if let Some(applied) = opt_applied {
+            if let Some(frame) = applied.frame {
+                commands.entity(frame).try_despawn();
+            }
+            if let (Some(emissive), Some(material)) =
+                (applied.emissive, materials.get_mut(&material.0))
+            {
+                material.emissive = emissive;
+                restored = Some(emissive);
+            }
+            commands
+                .entity(zone_entity)
+                .remove::<AppliedZoneHighlight>();
+        }
+        let Some(highlight) = wanted else {
+            continue;
+        };
+        let mut emissive = None;
+        let outline = query_shape
+            .get(zone_entity)
+            .cloned()
+            .unwrap_or_default()
+            .outline(zone.size);
+        let frame = match highlight.style {
+            ZoneHighlightStyle::Border { width } => Some(ring_mesh(&outline, width, false)),
+            ZoneHighlightStyle::Glow { width } => Some(ring_mesh(&outline, width, true)),
+            ZoneHighlightStyle::Tint { strength } => {
+                // 只修改这个场地自己的材质
+                if let Some(material) = own_zone_material(
+                    &mut commands,
+                    zone_entity,
+                    &material.0,
+                    query_owned.contains(zone_entity),
+                    &mut materials,
+                ) {
+                    let origin = restored.unwrap_or(material.emissive);
+                    material.emissive = origin + highlight.color.to_linear() * strength;
+                    emissive = Some(origin);
+                }
+                None
+            }
+        }
+        .map(|mesh| {
+            commands
+                .spawn((
+                    Name::new("zone highlight"),
+                    Mesh3d(meshes.add(mesh)),
+                    MeshMaterial3d(materials.add(StandardMaterial {
+                        base_color: highlight.color,
+                        alpha_mode: AlphaMode::Blend,
+                        unlit: true,
+                        cull_mode: None,
+                        ..default()
+                    })),
+                    // 高出场地一点 低于场地上的卡片
+                    Transform::from_xyz(0.0, 0.0, 0.001),
+                    Pickable::IGNORE,
+                    NotShadowCaster,
+                    ChildOf(zone_entity),
+                ))
+                .id()
+        });
+        commands.entity(zone_entity).insert(AppliedZoneHighlight {
+            highlight,
+            frame,
+            emissive,
+        });
+    }
+}
+
+/// 沿着逆时针的轮廓向外扩展 width 的环 fade 时外侧透明
+fn ring_mesh(outline: &[Vec2], width: f32, fade: bool) -> Mesh {
+    let count = outline.len();
+    let mut positions = Vec::with_capacity(count * 2);
+    let mut colors = Vec::with_capacity(count * 2);
+    let mut indices = Vec::with_capacity(count * 6);
+    for (i, point) in outline.iter().enumerate() {
+        let prev = outline[(i + count - 1) % count];
+        let next = outline[(i + 1) % count];
+        // 两条边的外法线
+        let n1 = (*point - prev).perp().normalize_or_zero() * -1.0;
+        let n2 = (next - *point).perp().normalize_or_zero() * -1.0;
+        let miter = (n1 + n2).normalize_or_zero();
+        let length = width / miter.dot(n1).max(0.1);
+        positions.push(point.extend(0.0));
+        positions.push((*point + miter * length).extend(0.0));
+        colors.push([1.0, 1.0, 1.0, 1.0]);
+        colors.push([1.0, 1.0, 1.0, if fade { 0.0 } else { 1.0 }]);
+        let (a, b) = (i as u32 * 2, ((i + 1) % count) as u32 * 2);
+        indices.extend([a, a + 1, b, b, a + 1, b + 1]);
+    }
+    let normals = vec![Vec3::Z; positions.len()];
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors)
+    .with_inserted_indices(Indices::U32(indices))
+}